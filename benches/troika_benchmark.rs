@@ -4,12 +4,15 @@ extern crate rand;
 use rand::{thread_rng, Rng};
 
 use criterion::Criterion;
-use troika::ftroika::Ftroika;
-use troika::troika::Troika;
-use troika::Sponge;
+use troika::ftroika::{FixedFtroika, Ftroika, Ftroika24};
+use troika::troika::{FixedTroika, Troika, Troika24};
+use troika::{hash_many, FtroikaBackend, Sponge, StreamingSponge, Trit, BATCH_SIZE};
 
 fn basic_troika() {
-    let mut troika = Troika::default();
+    sponge_troika(Troika::default());
+}
+
+fn sponge_troika<S: Sponge>(mut troika: S) {
     let mut input = [0u8; 8019];
     let mut output = [0u8; 243];
     let mut rng = thread_rng();
@@ -23,7 +26,10 @@ fn basic_troika() {
 }
 
 fn basic_ftroika() {
-    let mut ftroika = Ftroika::default();
+    backend_ftroika(Ftroika::default());
+}
+
+fn backend_ftroika(mut ftroika: Ftroika) {
     let mut input = [0u8; 8019];
     let mut output = [0u8; 243];
     let mut rng = thread_rng();
@@ -38,15 +44,147 @@ fn basic_ftroika() {
 
 fn ftroika_benchmark(c: &mut Criterion) {
     c.bench_function("Ftroika with input of 8019 trits", |b| {
-        b.iter(|| basic_ftroika())
+        b.iter(basic_ftroika)
     });
 }
 
+fn backend_benchmark(c: &mut Criterion) {
+    for &backend in [
+        FtroikaBackend::Scalar,
+        FtroikaBackend::Sse2,
+        FtroikaBackend::Avx2,
+    ]
+    .iter()
+    {
+        if let Ok(ftroika) = Ftroika::with_backend(backend) {
+            c.bench_function(
+                &format!("Ftroika {:?} with input of 8019 trits", backend),
+                move |b| b.iter(|| backend_ftroika(ftroika)),
+            );
+        }
+    }
+}
+
 fn troika_benchmark(c: &mut Criterion) {
-    c.bench_function("Troika with input of 8019 trits", |b| {
-        b.iter(|| basic_troika())
+    c.bench_function("Troika with input of 8019 trits", |b| b.iter(basic_troika));
+}
+
+fn fixed_rounds_benchmark(c: &mut Criterion) {
+    c.bench_function("Troika24 with input of 8019 trits", |b| {
+        b.iter(|| sponge_troika(Troika24::default()))
+    });
+    c.bench_function("Ftroika24 with input of 8019 trits", |b| {
+        b.iter(|| sponge_troika(Ftroika24::default()))
+    });
+    c.bench_function("Troika 12 rounds with input of 8019 trits", |b| {
+        b.iter(|| sponge_troika(Troika::new(12).unwrap()))
+    });
+    c.bench_function("FixedTroika<12> with input of 8019 trits", |b| {
+        b.iter(|| sponge_troika(FixedTroika::<12>::default()))
+    });
+    c.bench_function("Ftroika 12 rounds with input of 8019 trits", |b| {
+        b.iter(|| sponge_troika(Ftroika::new(12).unwrap()))
+    });
+    c.bench_function("FixedFtroika<12> with input of 8019 trits", |b| {
+        b.iter(|| sponge_troika(FixedFtroika::<12>::default()))
+    });
+}
+
+fn batch_benchmark(c: &mut Criterion) {
+    let mut inputs = vec![[0u8; 8019]; BATCH_SIZE];
+    let mut rng = thread_rng();
+
+    for input in inputs.iter_mut() {
+        for trit in input.iter_mut() {
+            *trit = rng.gen_range(0, 3);
+        }
+    }
+
+    c.bench_function("Batch of 64 inputs of 8019 trits", move |b| {
+        let messages: Vec<&[Trit]> = inputs.iter().map(|input| &input[..]).collect();
+        b.iter(|| hash_many(&messages))
     });
 }
 
-criterion_group!(benches, ftroika_benchmark, troika_benchmark);
+fn packed_benchmark(c: &mut Criterion) {
+    let mut input = [0u8; 8019];
+    let mut rng = thread_rng();
+
+    for trit in input.iter_mut() {
+        *trit = rng.gen_range(0, 3);
+    }
+    let packed = troika::encoding::trits_to_t5b1(&input).unwrap();
+    let trytes = troika::encoding::trits_to_trytes(&input).unwrap();
+
+    let bytes = packed.clone();
+    c.bench_function("Ftroika absorb_packed of 8019 trits", move |b| {
+        b.iter(|| Ftroika::default().absorb_packed(&bytes, 8019).unwrap())
+    });
+    c.bench_function("Ftroika unpack then absorb of 8019 trits", move |b| {
+        b.iter(|| {
+            let trits = troika::encoding::t5b1_to_trits(&packed, 8019).unwrap();
+            Ftroika::default().absorb_sequence(&trits)
+        })
+    });
+    c.bench_function("Ftroika absorb_trytes of 8019 trits", move |b| {
+        b.iter(|| Ftroika::default().absorb_trytes(&trytes).unwrap())
+    });
+    c.bench_function("Ftroika squeeze_packed of 8019 trits", |b| {
+        let mut bytes = [0u8; 1604];
+        b.iter(|| Ftroika::default().squeeze_packed(&mut bytes, 8019).unwrap())
+    });
+    c.bench_function("Ftroika squeeze then pack of 8019 trits", |b| {
+        let mut trits = [0u8; 8019];
+        b.iter(|| {
+            Ftroika::default().squeeze(&mut trits);
+            troika::encoding::trits_to_t5b1(&trits).unwrap()
+        })
+    });
+}
+
+fn compress_benchmark(c: &mut Criterion) {
+    let left = troika::hash(&[1; 243]);
+    let right = troika::hash(&[2; 243]);
+    c.bench_function("Compress two hashes", move |b| {
+        b.iter(|| troika::compress(&left, &right))
+    });
+    c.bench_function("Sponge of two hashes", move |b| {
+        let mut message = [0u8; 486];
+        message[..243].copy_from_slice(left.as_trits());
+        message[243..].copy_from_slice(right.as_trits());
+        b.iter(|| troika::hash(&message))
+    });
+}
+
+#[cfg(feature = "rayon")]
+fn par_batch_benchmark(c: &mut Criterion) {
+    let mut inputs = vec![[0u8; 8019]; 16 * BATCH_SIZE];
+    let mut rng = thread_rng();
+
+    for input in inputs.iter_mut() {
+        for trit in input.iter_mut() {
+            *trit = rng.gen_range(0, 3);
+        }
+    }
+
+    c.bench_function("Parallel batch of 1024 inputs of 8019 trits", move |b| {
+        let messages: Vec<&[Trit]> = inputs.iter().map(|input| &input[..]).collect();
+        b.iter(|| troika::par_hash_batch(&messages))
+    });
+}
+
+#[cfg(not(feature = "rayon"))]
+fn par_batch_benchmark(_: &mut Criterion) {}
+
+criterion_group!(
+    benches,
+    ftroika_benchmark,
+    backend_benchmark,
+    troika_benchmark,
+    fixed_rounds_benchmark,
+    batch_benchmark,
+    par_batch_benchmark,
+    compress_benchmark,
+    packed_benchmark
+);
 criterion_main!(benches);
@@ -0,0 +1,233 @@
+use super::constants::Trit;
+use crate::ftroika::{Ftroika, FtroikaBackend};
+use crate::hasher::StreamingSponge;
+use crate::params::Tables;
+use crate::troika::Troika;
+use crate::{Midstate, Result, Sponge};
+
+/// The Backend enum names every Troika implementation of this crate.
+/// All backends give identical results.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// The reference implementation, one trit per byte.
+    Reference,
+    /// Ftroika on portable `u32` bit-planes.
+    Ftroika,
+    /// Ftroika on x86_64 SSE2 vectors.
+    FtroikaSse2,
+    /// Ftroika on x86_64 AVX2 vectors.
+    FtroikaAvx2,
+}
+
+impl Backend {
+    /// Every backend, whether the running CPU supports it or not.
+    pub const ALL: [Backend; 4] = [
+        Backend::Reference,
+        Backend::Ftroika,
+        Backend::FtroikaSse2,
+        Backend::FtroikaAvx2,
+    ];
+
+    /// The fastest backend supported by the running CPU.
+    pub fn detect() -> Backend {
+        Backend::from(FtroikaBackend::detect())
+    }
+
+    pub fn is_supported(self) -> bool {
+        match self.ftroika_backend() {
+            Some(backend) => backend.is_supported(),
+            None => true,
+        }
+    }
+
+    fn ftroika_backend(self) -> Option<FtroikaBackend> {
+        match self {
+            Backend::Reference => None,
+            Backend::Ftroika => Some(FtroikaBackend::Scalar),
+            Backend::FtroikaSse2 => Some(FtroikaBackend::Sse2),
+            Backend::FtroikaAvx2 => Some(FtroikaBackend::Avx2),
+        }
+    }
+}
+
+impl From<FtroikaBackend> for Backend {
+    fn from(backend: FtroikaBackend) -> Backend {
+        match backend {
+            FtroikaBackend::Scalar => Backend::Ftroika,
+            FtroikaBackend::Sse2 => Backend::FtroikaSse2,
+            FtroikaBackend::Avx2 => Backend::FtroikaAvx2,
+        }
+    }
+}
+
+/// The AnyTroika enum is a Sponge whose implementation is chosen at
+/// runtime, e.g. to run several backends side by side.
+// Boxing the reference state would need alloc and lose Copy
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Copy, Debug)]
+pub enum AnyTroika {
+    Reference(Troika),
+    Ftroika(Ftroika),
+}
+
+impl Default for AnyTroika {
+    fn default() -> AnyTroika {
+        AnyTroika::Ftroika(Ftroika::default())
+    }
+}
+
+impl AnyTroika {
+    /// Create a sponge on the given backend.
+    /// Fails if the running CPU does not support it.
+    pub fn new(backend: Backend) -> Result<AnyTroika> {
+        match backend.ftroika_backend() {
+            None => Ok(AnyTroika::Reference(Troika::default())),
+            Some(backend) => Ftroika::with_backend(backend).map(AnyTroika::Ftroika),
+        }
+    }
+
+    pub fn backend(&self) -> Backend {
+        match self {
+            AnyTroika::Reference(_) => Backend::Reference,
+            AnyTroika::Ftroika(ftroika) => Backend::from(ftroika.backend()),
+        }
+    }
+
+    /// Take a snapshot of the sponge.
+    pub fn midstate(&self) -> Midstate {
+        match self {
+            AnyTroika::Reference(troika) => troika.midstate(),
+            AnyTroika::Ftroika(ftroika) => ftroika.midstate(),
+        }
+    }
+
+    /// Continue from a snapshot taken on any backend.
+    pub fn restore(&mut self, midstate: &Midstate) {
+        match self {
+            AnyTroika::Reference(troika) => troika.restore(midstate),
+            AnyTroika::Ftroika(ftroika) => ftroika.restore(midstate),
+        }
+    }
+
+    pub(crate) fn set_params(&mut self, num_rounds: usize, tables: &'static Tables) {
+        match self {
+            AnyTroika::Reference(troika) => troika.set_params(num_rounds, tables),
+            AnyTroika::Ftroika(ftroika) => ftroika.set_params(num_rounds, tables),
+        }
+    }
+}
+
+impl Sponge for AnyTroika {
+    fn absorb(&mut self, trits: &[Trit]) {
+        match self {
+            AnyTroika::Reference(troika) => troika.absorb(trits),
+            AnyTroika::Ftroika(ftroika) => ftroika.absorb(trits),
+        }
+    }
+
+    fn squeeze(&mut self, trits: &mut [Trit]) {
+        match self {
+            AnyTroika::Reference(troika) => troika.squeeze(trits),
+            AnyTroika::Ftroika(ftroika) => ftroika.squeeze(trits),
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            AnyTroika::Reference(troika) => troika.reset(),
+            AnyTroika::Ftroika(ftroika) => ftroika.reset(),
+        }
+    }
+}
+
+impl StreamingSponge for AnyTroika {
+    fn absorb_sequence(&mut self, trits: &[Trit]) {
+        match self {
+            AnyTroika::Reference(troika) => troika.absorb_sequence(trits),
+            AnyTroika::Ftroika(ftroika) => ftroika.absorb_sequence(trits),
+        }
+    }
+
+    fn absorb_padding(&mut self) {
+        match self {
+            AnyTroika::Reference(troika) => troika.absorb_padding(),
+            AnyTroika::Ftroika(ftroika) => ftroika.absorb_padding(),
+        }
+    }
+}
+
+impl From<Troika> for AnyTroika {
+    fn from(troika: Troika) -> AnyTroika {
+        AnyTroika::Reference(troika)
+    }
+}
+
+impl From<Ftroika> for AnyTroika {
+    fn from(ftroika: Ftroika) -> AnyTroika {
+        AnyTroika::Ftroika(ftroika)
+    }
+}
+
+#[cfg(test)]
+mod test_backend {
+    use super::*;
+    use crate::fixtures::message;
+    use crate::{Error, TroikaHasher};
+
+    #[test]
+    fn test_backends_agree() {
+        for &length in [0, 1, 242, 243, 244, 1000].iter() {
+            let message: [Trit; 1000] = message(length);
+            let mut expected = [0; 500];
+            let mut troika = Troika::default();
+            troika.absorb(&message[..length]);
+            troika.squeeze(&mut expected);
+
+            for &backend in Backend::ALL.iter().filter(|b| b.is_supported()) {
+                let mut sponge = AnyTroika::new(backend).unwrap();
+                assert_eq!(sponge.backend(), backend);
+                let mut output = [0; 500];
+                sponge.absorb(&message[..length]);
+                sponge.squeeze(&mut output);
+                assert_eq!(output[..], expected[..], "{:?}", backend);
+
+                let mut hasher = TroikaHasher::with_sponge(AnyTroika::new(backend).unwrap());
+                hasher.update(&message[..length]);
+                assert_eq!(hasher.finalize().as_trits(), &expected[..243]);
+            }
+        }
+
+        // Absorbing after a partial squeeze continues at the same position
+        let input: [Trit; 300] = message(300);
+        for &squeezed in [0, 100, 243, 300].iter() {
+            let duplex = |mut sponge: AnyTroika| {
+                sponge.absorb(&input[..10]);
+                sponge.squeeze(&mut [0; 300][..squeezed]);
+                sponge.absorb_sequence(&input[..50]);
+                let midstate = sponge.midstate();
+                sponge.absorb(&input);
+                let mut output = [0; 243];
+                sponge.squeeze(&mut output);
+                (midstate, output)
+            };
+            let expected = duplex(AnyTroika::new(Backend::Reference).unwrap());
+            for &backend in Backend::ALL.iter().filter(|b| b.is_supported()) {
+                let result = duplex(AnyTroika::new(backend).unwrap());
+                assert_eq!(result, expected, "{:?} squeezed {}", backend, squeezed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect() {
+        assert!(Backend::detect().is_supported());
+        assert!(Backend::Reference.is_supported());
+        assert_eq!(AnyTroika::default().backend(), Backend::detect());
+        for &backend in Backend::ALL.iter().filter(|b| !b.is_supported()) {
+            assert_eq!(
+                AnyTroika::new(backend).unwrap_err(),
+                Error::UnsupportedBackend
+            );
+        }
+    }
+}
@@ -0,0 +1,387 @@
+use super::constants::{
+    Trit, COLUMNS, PADDING, PARITY_NEIGHBOURS, ROUND_CONSTANTS, SHIFT_ROWS_LANES_DEST, SLICES,
+    SLICESIZE, STATE_SIZE, TROIKA_RATE,
+};
+use crate::Hash;
+#[cfg(feature = "rayon")]
+use alloc::vec::IntoIter;
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Number of independent states processed by one permutation.
+pub const BATCH_SIZE: usize = 64;
+
+/// The T64 struct holds one trit of each of the 64 instances, in the
+/// same p/n representation Ftroika uses for its slices.
+#[derive(Clone, Copy, Default)]
+struct T64 {
+    p: u64,
+    n: u64,
+}
+
+impl T64 {
+    fn new(p: u64, n: u64) -> T64 {
+        T64 { p, n }
+    }
+
+    /// The addition of `T27`.
+    fn add(&self, other: &T64) -> T64 {
+        let t = (self.p | other.n) ^ (self.n | other.p);
+        let p = (self.n | other.n) ^ t;
+        let n = (self.p | other.p) ^ t;
+        T64::new(p, n)
+    }
+
+    fn mul(&self, other: &T64) -> T64 {
+        let p = (self.p & other.p) | (self.n & other.n);
+        let n = (self.p & other.n) | (self.n & other.p);
+        T64::new(p, n)
+    }
+
+    /// The same trit in every instance.
+    fn splat(trit: Trit) -> T64 {
+        SPLAT[usize::from(trit)]
+    }
+
+    /// Set the trit of `lane`, which must be zero.
+    fn or_lane(&mut self, lane: usize, trit: Trit) {
+        // Like Ftroika, a value above 2 is read as 0
+        let [p, n] = LANE_BITS
+            .get(usize::from(trit))
+            .copied()
+            .unwrap_or_default();
+        self.p |= p << lane;
+        self.n |= n << lane;
+    }
+
+    fn get(&self, lane: usize) -> Trit {
+        let mask = 1u64 << lane;
+        if self.p & mask != 0 {
+            1
+        } else if self.n & mask != 0 {
+            2
+        } else {
+            0
+        }
+    }
+}
+
+const SPLAT: [T64; 3] = [T64 { p: 0, n: 0 }, T64 { p: !0, n: 0 }, T64 { p: 0, n: !0 }];
+
+/// The p and n bits of every trit in lane 0.
+const LANE_BITS: [[u64; 2]; 3] = [[0, 0], [1, 0], [0, 1]];
+
+/// The Btroika struct runs the Troika permutation on 64 independent
+/// states at once. Trit `i` of every state is stored in `state[i]`,
+/// one bit per instance.
+#[derive(Clone, Copy)]
+struct Btroika {
+    state: [T64; STATE_SIZE],
+}
+
+impl Btroika {
+    fn new() -> Btroika {
+        Btroika {
+            state: [T64::default(); STATE_SIZE],
+        }
+    }
+
+    /// Clear the rate of the lanes in `mask`, before loading their blocks.
+    fn clear_rate(&mut self, mask: u64) {
+        for lanes in self.state[..TROIKA_RATE].iter_mut() {
+            lanes.p &= !mask;
+            lanes.n &= !mask;
+        }
+    }
+
+    /// Write the `block` of `message` into the cleared rate of `lane`,
+    /// padding it if it is the last one.
+    fn load_block(&mut self, lane: usize, message: &[Trit], block: usize) {
+        let start = (block * TROIKA_RATE).min(message.len());
+        let end = (start + TROIKA_RATE).min(message.len());
+        let trits = &message[start..end];
+        for (lanes, &trit) in self.state.iter_mut().zip(trits) {
+            lanes.or_lane(lane, trit);
+        }
+        if trits.len() < TROIKA_RATE {
+            self.state[trits.len()].or_lane(lane, PADDING);
+        }
+    }
+
+    fn store_hash(&self, lane: usize) -> Hash {
+        let mut hash = [0; TROIKA_RATE];
+        for (trit, lanes) in hash.iter_mut().zip(self.state.iter()) {
+            *trit = lanes.get(lane);
+        }
+        Hash::from(hash)
+    }
+
+    fn permutation(&mut self) {
+        let mut scratch = [T64::default(); STATE_SIZE];
+        for constants in ROUND_CONSTANTS.iter() {
+            self.round(&mut scratch, constants);
+        }
+    }
+
+    /// One round in the three passes of `Troika::round`: the S-box
+    /// outputs are scattered to their shifted positions in `scratch`,
+    /// the column parities are summed, and the mixed trits are written
+    /// back with the round constants.
+    #[inline(always)]
+    fn round(&mut self, scratch: &mut [T64; STATE_SIZE], constants: &[Trit; COLUMNS * SLICES]) {
+        for (a, dest) in self
+            .state
+            .chunks_exact(3)
+            .zip(SHIFT_ROWS_LANES_DEST.chunks_exact(3))
+        {
+            let d = a[0].add(&T64::splat(2));
+            let e = d.mul(&a[1]).add(&a[2]);
+            let f = e.mul(&a[1]).add(&d);
+            let g = e.mul(&f).add(&a[1]);
+            scratch[usize::from(dest[0])] = g;
+            scratch[usize::from(dest[1])] = f;
+            scratch[usize::from(dest[2])] = e;
+        }
+
+        let mut parity = [T64::default(); COLUMNS * SLICES];
+        for (slice, parity) in scratch
+            .chunks_exact(SLICESIZE)
+            .zip(parity.chunks_exact_mut(COLUMNS))
+        {
+            let (row0, rows12) = slice.split_at(COLUMNS);
+            let (row1, row2) = rows12.split_at(COLUMNS);
+            for (((col_sum, a), b), c) in parity.iter_mut().zip(row0).zip(row1).zip(row2) {
+                *col_sum = a.add(b).add(c);
+            }
+        }
+        let mut sums = [T64::default(); COLUMNS * SLICES];
+        for (sum, &[left, right]) in sums.iter_mut().zip(PARITY_NEIGHBOURS.iter()) {
+            *sum = parity[usize::from(left)].add(&parity[usize::from(right)]);
+        }
+
+        for (((state, shifted), sums), constants) in self
+            .state
+            .chunks_exact_mut(SLICESIZE)
+            .zip(scratch.chunks_exact(SLICESIZE))
+            .zip(sums.chunks_exact(COLUMNS))
+            .zip(constants.chunks_exact(COLUMNS))
+        {
+            let (row0, rows12) = state.split_at_mut(COLUMNS);
+            for (((lanes, shifted), sum), &constant) in
+                row0.iter_mut().zip(shifted).zip(sums).zip(constants)
+            {
+                *lanes = shifted.add(&sum.add(&T64::splat(constant)));
+            }
+            for (row, shifted) in rows12
+                .chunks_exact_mut(COLUMNS)
+                .zip(shifted[COLUMNS..].chunks_exact(COLUMNS))
+            {
+                for ((lanes, shifted), sum) in row.iter_mut().zip(shifted).zip(sums) {
+                    *lanes = shifted.add(sum);
+                }
+            }
+        }
+    }
+}
+
+/// Hash up to `BATCH_SIZE` messages with a single bitsliced state.
+fn hash_batch(messages: &[&[Trit]], hashes: &mut Vec<Hash>) {
+    debug_assert!(messages.len() <= BATCH_SIZE);
+    let mut troika = Btroika::new();
+    // Every message is followed by the padding trit
+    let blocks: Vec<usize> = messages
+        .iter()
+        .map(|message| message.len() / TROIKA_RATE + 1)
+        .collect();
+    let max_blocks = blocks.iter().cloned().max().unwrap_or(0);
+    let mut batch = vec![Hash::from([0; TROIKA_RATE]); messages.len()];
+
+    for block in 0..max_blocks {
+        let loaded = blocks
+            .iter()
+            .enumerate()
+            .filter(|&(_, &blocks)| block < blocks)
+            .fold(0, |mask, (lane, _)| mask | 1 << lane);
+        troika.clear_rate(loaded);
+        for (lane, message) in messages.iter().enumerate() {
+            if block < blocks[lane] {
+                troika.load_block(lane, message, block);
+            }
+        }
+        troika.permutation();
+        for (lane, hash) in batch.iter_mut().enumerate() {
+            if block + 1 == blocks[lane] {
+                *hash = troika.store_hash(lane);
+            }
+        }
+    }
+    hashes.extend(batch);
+}
+
+/// Hash many messages with the bitsliced engine, `BATCH_SIZE` at a time.
+/// The hashes are returned in the order of `messages` and are identical
+/// to hashing each message with `Ftroika`.
+pub fn hash_many(messages: &[&[Trit]]) -> Vec<Hash> {
+    let mut hashes = Vec::with_capacity(messages.len());
+    for batch in messages.chunks(BATCH_SIZE) {
+        hash_batch(batch, &mut hashes);
+    }
+    hashes
+}
+
+/// Below this many blocks per permutation on average, a batch leaves most
+/// lanes of the bitsliced state idle and is cheaper to hash one message
+/// at a time.
+#[cfg(feature = "rayon")]
+const MIN_BATCH_FILL: usize = BATCH_SIZE / 2;
+
+/// Hash up to `BATCH_SIZE` messages with the bitsliced engine if they
+/// fill it, and otherwise with `Ftroika` on the fastest backend.
+#[cfg(feature = "rayon")]
+fn hash_chunk(messages: &[&[Trit]]) -> Vec<Hash> {
+    let blocks = |message: &&[Trit]| message.len() / TROIKA_RATE + 1;
+    let total_blocks: usize = messages.iter().map(blocks).sum();
+    let max_blocks = messages.iter().map(blocks).max().unwrap_or(0);
+    if total_blocks >= MIN_BATCH_FILL * max_blocks {
+        let mut hashes = Vec::with_capacity(messages.len());
+        hash_batch(messages, &mut hashes);
+        hashes
+    } else {
+        messages
+            .iter()
+            .map(|message| crate::hash(message))
+            .collect()
+    }
+}
+
+/// Hash many messages on the rayon thread pool, `BATCH_SIZE` per task.
+/// The hashes are returned in the order of `messages` and are identical
+/// to hashing each message with `Ftroika`.
+#[cfg(feature = "rayon")]
+pub fn par_hash_batch(messages: &[&[Trit]]) -> Vec<Hash> {
+    let batches: Vec<Vec<Hash>> = messages.par_chunks(BATCH_SIZE).map(hash_chunk).collect();
+    batches.concat()
+}
+
+/// The ParBatchHasher struct hashes the messages of an iterator with
+/// `par_hash_batch` and yields their hashes in order. It takes one batch
+/// per thread from the iterator at a time, so only those messages need
+/// to be in memory.
+#[cfg(feature = "rayon")]
+pub struct ParBatchHasher<I> {
+    messages: I,
+    hashes: IntoIter<Hash>,
+}
+
+#[cfg(feature = "rayon")]
+impl<I> ParBatchHasher<I>
+where
+    I: Iterator,
+    I::Item: AsRef<[Trit]>,
+{
+    pub fn new(messages: impl IntoIterator<IntoIter = I>) -> ParBatchHasher<I> {
+        ParBatchHasher {
+            messages: messages.into_iter(),
+            hashes: Vec::new().into_iter(),
+        }
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<I> Iterator for ParBatchHasher<I>
+where
+    I: Iterator,
+    I::Item: AsRef<[Trit]>,
+{
+    type Item = Hash;
+
+    fn next(&mut self) -> Option<Hash> {
+        if self.hashes.len() == 0 {
+            let group: Vec<I::Item> = self
+                .messages
+                .by_ref()
+                .take(BATCH_SIZE * rayon::current_num_threads())
+                .collect();
+            let messages: Vec<&[Trit]> = group.iter().map(AsRef::as_ref).collect();
+            self.hashes = par_hash_batch(&messages).into_iter();
+        }
+        self.hashes.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.messages.size_hint();
+        let pending = self.hashes.len();
+        (
+            lower.saturating_add(pending),
+            upper.and_then(|upper| upper.checked_add(pending)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test_batch {
+    use super::*;
+    use crate::fixtures::trits;
+
+    #[test]
+    fn test_matches_ftroika() {
+        let messages: Vec<Vec<Trit>> = (0..150)
+            .map(|i| (i * 37) % 900)
+            .enumerate()
+            .map(|(i, l)| trits::<900>(l, i)[..l].to_vec())
+            .collect();
+        let slices: Vec<&[Trit]> = messages.iter().map(|m| &m[..]).collect();
+        let hashes = hash_many(&slices);
+
+        assert_eq!(hashes.len(), messages.len());
+        for (message, hash) in messages.iter().zip(hashes.iter()) {
+            assert_eq!(*hash, crate::hash(message));
+        }
+    }
+
+    #[test]
+    fn test_invalid_trits() {
+        let mut messages: Vec<[Trit; 300]> = (0..BATCH_SIZE).map(|i| trits(300, i)).collect();
+        for (i, message) in messages.iter_mut().enumerate() {
+            message[i] = 3;
+            message[100 + i] = 255;
+        }
+        let slices: Vec<&[Trit]> = messages.iter().map(|m| &m[..]).collect();
+        let expected: Vec<Hash> = slices.iter().map(|m| crate::hash(m)).collect();
+
+        assert_eq!(hash_many(&slices), expected);
+        #[cfg(feature = "rayon")]
+        assert_eq!(par_hash_batch(&slices), expected);
+    }
+
+    #[test]
+    fn test_empty_batch() {
+        assert!(hash_many(&[]).is_empty());
+        assert_eq!(hash_many(&[&[]]), vec![crate::hash(&[])]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel() {
+        let messages: Vec<Vec<Trit>> = (0..300)
+            .map(|i| (i * 53) % 2000)
+            .enumerate()
+            .map(|(i, l)| trits::<2000>(l, i)[..l].to_vec())
+            .collect();
+        let slices: Vec<&[Trit]> = messages.iter().map(|m| &m[..]).collect();
+        let expected: Vec<Hash> = slices.iter().map(|m| crate::hash(m)).collect();
+
+        assert_eq!(par_hash_batch(&slices), expected);
+        assert_eq!(ParBatchHasher::new(&messages).collect::<Vec<_>>(), expected);
+        // Too few messages to fill the bitsliced state go through Ftroika
+        assert_eq!(par_hash_batch(&slices[..3]), expected[..3].to_vec());
+        assert_eq!(
+            hash_chunk(&slices[..BATCH_SIZE]),
+            expected[..BATCH_SIZE].to_vec()
+        );
+
+        assert!(par_hash_batch(&[]).is_empty());
+        assert_eq!(ParBatchHasher::new(Vec::<Vec<Trit>>::new()).next(), None);
+    }
+}
@@ -0,0 +1,510 @@
+//! Conversions between the crate's trits and other representations.
+//!
+//! A [`Trit`] holds 0, 1 or 2, where 2 stands for the balanced
+//! trit -1. All multi-trit values are little-endian.
+//!
+//! The conversions returning a `Vec` or `String` need the `alloc` feature.
+
+use super::constants::Trit;
+use crate::{Error, Result, TritSlice};
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec, vec::Vec};
+use core::convert::TryFrom;
+
+/// The IOTA tryte alphabet, ordered by tryte value 0..=13 followed by -13..=-1.
+pub const TRYTE_ALPHABET: &[u8; 27] = b"9ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+pub(crate) const TRITS_PER_TRYTE: usize = 3;
+pub(crate) const TRITS_PER_T5B1: usize = 5;
+#[cfg(feature = "alloc")]
+const TRITS_PER_B1T6: usize = 6;
+const MAX_T5B1: i8 = 121;
+
+/// The trits of every tryte, in the order of `TRYTE_ALPHABET`.
+const TRYTES: [[Trit; TRITS_PER_TRYTE]; 27] = {
+    let mut trytes = [[0; TRITS_PER_TRYTE]; 27];
+    let mut idx = 0;
+    while idx < 27 {
+        trytes[idx] = balanced_digits(if idx > 13 {
+            idx as i16 - 27
+        } else {
+            idx as i16
+        });
+        idx += 1;
+    }
+    trytes
+};
+
+/// The trits of every T5B1 byte, from -121 up.
+const T5B1_TRITS: [[Trit; TRITS_PER_T5B1]; 2 * MAX_T5B1 as usize + 1] = {
+    let mut trits = [[0; TRITS_PER_T5B1]; 2 * MAX_T5B1 as usize + 1];
+    let mut idx = 0;
+    while idx < trits.len() {
+        trits[idx] = balanced_digits(idx as i16 - MAX_T5B1 as i16);
+        idx += 1;
+    }
+    trits
+};
+
+/// Trits as a pair of bit masks: bit `k` of the first mask is set if
+/// trit `k` is 1, and of the second if it is 2.
+pub(crate) type TritMasks = [u8; 2];
+
+/// The masks of every T5B1 byte. Out of range bytes map to zero.
+const T5B1_MASKS: [TritMasks; 256] = {
+    let mut masks = [[0; 2]; 256];
+    let mut idx = 0;
+    while idx < T5B1_TRITS.len() {
+        let byte = (idx as i16 - MAX_T5B1 as i16) as i8 as u8;
+        masks[byte as usize] = trit_masks(&T5B1_TRITS[idx]);
+        idx += 1;
+    }
+    masks
+};
+
+/// The masks of every tryte character. Other characters map to zero.
+const TRYTE_MASKS: [TritMasks; 256] = {
+    let mut masks = [[0; 2]; 256];
+    let mut idx = 0;
+    while idx < 27 {
+        masks[TRYTE_ALPHABET[idx] as usize] = trit_masks(&TRYTES[idx]);
+        idx += 1;
+    }
+    masks
+};
+
+/// The sum of `3^k` over the set bits `k` of a mask of five trits.
+const MASK_VALUES: [i8; 32] = {
+    let mut values = [0; 32];
+    let mut mask = 0;
+    while mask < 32 {
+        let mut bit = TRITS_PER_T5B1;
+        while bit > 0 {
+            bit -= 1;
+            values[mask] = 3 * values[mask] + ((mask >> bit) & 1) as i8;
+        }
+        mask += 1;
+    }
+    values
+};
+
+const fn trit_masks<const N: usize>(trits: &[Trit; N]) -> TritMasks {
+    let mut masks = [0; 2];
+    let mut idx = 0;
+    while idx < N {
+        match trits[idx] {
+            1 => masks[0] |= 1 << idx,
+            2 => masks[1] |= 1 << idx,
+            _ => (),
+        }
+        idx += 1;
+    }
+    masks
+}
+
+/// The first `N` balanced ternary digits of `value`, the same as
+/// `write_value` but usable in constants.
+const fn balanced_digits<const N: usize>(mut value: i16) -> [Trit; N] {
+    let mut trits = [0; N];
+    let mut idx = 0;
+    while idx < N {
+        let rem = value.rem_euclid(3);
+        trits[idx] = rem as Trit;
+        value = value.div_euclid(3) + if rem == 2 { 1 } else { 0 };
+        idx += 1;
+    }
+    trits
+}
+
+fn to_balanced(trit: Trit) -> i8 {
+    match trit {
+        2 => -1,
+        trit => trit as i8,
+    }
+}
+
+/// Sum up the balanced value of little-endian trits that are known to be valid.
+fn trits_value(trits: &[Trit]) -> Option<i64> {
+    // Accumulate in a wider type since a prefix of a valid i64 may overflow
+    let mut value = 0i128;
+    for &trit in trits.iter().rev() {
+        value = value
+            .checked_mul(3)?
+            .checked_add(i128::from(to_balanced(trit)))?;
+    }
+    i64::try_from(value).ok()
+}
+
+/// Write the balanced ternary digits of `value` into `trits`.
+/// The caller makes sure `value` fits in `trits.len()` trits.
+#[cfg(feature = "alloc")]
+fn write_value(mut value: i64, trits: &mut [Trit]) {
+    for trit in trits.iter_mut() {
+        let rem = value.rem_euclid(3);
+        *trit = rem as Trit;
+        // A remainder of 2 is the digit -1 with a carry into the next trit
+        value = value.div_euclid(3) + if rem == 2 { 1 } else { 0 };
+    }
+    debug_assert_eq!(value, 0);
+}
+
+#[cfg(feature = "alloc")]
+fn check_multiple(length: usize, multiple: usize) -> Result<()> {
+    if !length.is_multiple_of(multiple) {
+        return Err(Error::InvalidLength {
+            expected: length.div_ceil(multiple) * multiple,
+            actual: length,
+        });
+    }
+    Ok(())
+}
+
+/// Convert balanced trits (-1, 0, 1) into the crate's trit form.
+#[cfg(feature = "alloc")]
+pub fn balanced_to_trits(balanced: &[i8]) -> Result<Vec<Trit>> {
+    balanced
+        .iter()
+        .enumerate()
+        .map(|(index, &trit)| match trit {
+            -1 => Ok(2),
+            0 | 1 => Ok(trit as Trit),
+            _ => Err(Error::InvalidEncoding {
+                encoding: "balanced trit",
+                index,
+            }),
+        })
+        .collect()
+}
+
+/// Convert trits into balanced trits (-1, 0, 1).
+#[cfg(feature = "alloc")]
+pub fn trits_to_balanced(trits: &[Trit]) -> Result<Vec<i8>> {
+    let trits = TritSlice::new(trits)?;
+    Ok(trits
+        .as_trits()
+        .iter()
+        .map(|&trit| to_balanced(trit))
+        .collect())
+}
+
+/// The balanced value of at most five valid trits.
+fn small_value(trits: &[Trit]) -> i8 {
+    trits
+        .iter()
+        .rev()
+        .fold(0, |value, &trit| 3 * value + to_balanced(trit))
+}
+
+/// Map three valid trits to their tryte character.
+pub(crate) fn tryte_to_char(tryte: &[Trit]) -> char {
+    tryte_byte(tryte) as char
+}
+
+/// Map three valid trits to the ASCII code of their tryte character.
+pub(crate) fn tryte_byte(tryte: &[Trit]) -> u8 {
+    TRYTE_ALPHABET[small_value(tryte).rem_euclid(27) as usize]
+}
+
+/// The trits of the tryte `c`, or `None` if it is not in the tryte
+/// alphabet.
+pub(crate) fn tryte_trits(c: u8) -> Option<[Trit; TRITS_PER_TRYTE]> {
+    let idx = match c {
+        b'9' => 0,
+        b'A'..=b'Z' => c - b'A' + 1,
+        _ => return None,
+    };
+    Some(TRYTES[idx as usize])
+}
+
+/// Write the trits of the tryte `c`, or return `None` if it is not in
+/// the tryte alphabet.
+pub(crate) fn char_to_tryte(c: u8, tryte: &mut [Trit]) -> Option<()> {
+    tryte.copy_from_slice(&tryte_trits(c)?);
+    Some(())
+}
+
+/// The masks of the tryte `c`, which must be in the tryte alphabet.
+pub(crate) fn tryte_masks(c: u8) -> TritMasks {
+    TRYTE_MASKS[usize::from(c)]
+}
+
+/// The tryte character of the masks of three trits.
+pub(crate) fn masks_tryte_byte([p, n]: TritMasks) -> u8 {
+    let value = MASK_VALUES[usize::from(p)] - MASK_VALUES[usize::from(n)];
+    TRYTE_ALPHABET[value.rem_euclid(27) as usize]
+}
+
+/// Check that `trytes` are all in the tryte alphabet.
+pub(crate) fn check_trytes(trytes: &str) -> Result<()> {
+    match trytes.bytes().position(|c| tryte_trits(c).is_none()) {
+        Some(index) => Err(Error::InvalidEncoding {
+            encoding: "tryte",
+            index,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Convert trits into a tryte string. The number of trits must be a
+/// multiple of three.
+#[cfg(feature = "alloc")]
+pub fn trits_to_trytes(trits: &[Trit]) -> Result<String> {
+    check_multiple(trits.len(), TRITS_PER_TRYTE)?;
+    let trits = TritSlice::new(trits)?;
+    Ok(trits
+        .as_trits()
+        .chunks(TRITS_PER_TRYTE)
+        .map(tryte_to_char)
+        .collect())
+}
+
+/// Convert a tryte string into trits.
+#[cfg(feature = "alloc")]
+pub fn trytes_to_trits(trytes: &str) -> Result<Vec<Trit>> {
+    let mut trits = vec![0; trytes.len() * TRITS_PER_TRYTE];
+    for (index, (tryte, c)) in trits
+        .chunks_mut(TRITS_PER_TRYTE)
+        .zip(trytes.bytes())
+        .enumerate()
+    {
+        char_to_tryte(c, tryte).ok_or(Error::InvalidEncoding {
+            encoding: "tryte",
+            index,
+        })?;
+    }
+    Ok(trits)
+}
+
+/// Pack valid trits into `bytes`, which holds exactly enough T5B1 bytes.
+pub(crate) fn pack_t5b1(trits: &[Trit], bytes: &mut [u8]) {
+    debug_assert_eq!(bytes.len(), trits.len().div_ceil(TRITS_PER_T5B1));
+    for (byte, chunk) in bytes.iter_mut().zip(trits.chunks(TRITS_PER_T5B1)) {
+        *byte = t5b1_byte(chunk);
+    }
+}
+
+/// Pack at most five valid trits into a T5B1 byte.
+pub(crate) fn t5b1_byte(trits: &[Trit]) -> u8 {
+    small_value(trits) as u8
+}
+
+/// The trits of the T5B1 `byte`, or `None` if it is out of range.
+pub(crate) fn t5b1_trits(byte: u8) -> Option<[Trit; TRITS_PER_T5B1]> {
+    T5B1_TRITS
+        .get((i16::from(byte as i8) + i16::from(MAX_T5B1)) as usize)
+        .copied()
+}
+
+/// The masks of the T5B1 `byte`, which must be in range.
+pub(crate) fn t5b1_masks(byte: u8) -> TritMasks {
+    T5B1_MASKS[usize::from(byte)]
+}
+
+/// The T5B1 byte of the masks of at most five trits.
+pub(crate) fn masks_t5b1_byte([p, n]: TritMasks) -> u8 {
+    (MASK_VALUES[usize::from(p)] - MASK_VALUES[usize::from(n)]) as u8
+}
+
+/// Check that `bytes` is exactly as long as `num_trits` trits packed
+/// five per byte, that every byte is in range and that the trits
+/// padding the last byte are zero.
+pub(crate) fn check_t5b1(bytes: &[u8], num_trits: usize) -> Result<()> {
+    check_t5b1_length(bytes.len(), num_trits)?;
+    for (index, &byte) in bytes.iter().enumerate() {
+        let used = (num_trits - TRITS_PER_T5B1 * index).min(TRITS_PER_T5B1);
+        match t5b1_trits(byte) {
+            Some(trits) if trits[used..].iter().all(|&trit| trit == 0) => (),
+            _ => {
+                return Err(Error::InvalidEncoding {
+                    encoding: "T5B1",
+                    index,
+                })
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check that `length` bytes hold `num_trits` trits packed five per byte.
+pub(crate) fn check_t5b1_length(length: usize, num_trits: usize) -> Result<()> {
+    let expected = num_trits.div_ceil(TRITS_PER_T5B1);
+    if length != expected {
+        return Err(Error::InvalidLength {
+            expected,
+            actual: length,
+        });
+    }
+    Ok(())
+}
+
+/// Unpack T5B1 `bytes` into all of `trits`.
+pub(crate) fn unpack_t5b1(bytes: &[u8], trits: &mut [Trit]) -> Result<()> {
+    check_t5b1(bytes, trits.len())?;
+    for (chunk, &byte) in trits.chunks_mut(TRITS_PER_T5B1).zip(bytes.iter()) {
+        if let Some(unpacked) = t5b1_trits(byte) {
+            chunk.copy_from_slice(&unpacked[..chunk.len()]);
+        }
+    }
+    Ok(())
+}
+
+/// Pack trits five per byte (T5B1). The last byte is padded with zero trits.
+#[cfg(feature = "alloc")]
+pub fn trits_to_t5b1(trits: &[Trit]) -> Result<Vec<u8>> {
+    let trits = TritSlice::new(trits)?;
+    let mut bytes = vec![0; trits.as_trits().len().div_ceil(TRITS_PER_T5B1)];
+    pack_t5b1(trits.as_trits(), &mut bytes);
+    Ok(bytes)
+}
+
+/// Unpack `num_trits` trits from T5B1 bytes.
+#[cfg(feature = "alloc")]
+pub fn t5b1_to_trits(bytes: &[u8], num_trits: usize) -> Result<Vec<Trit>> {
+    let mut trits = vec![0; num_trits];
+    unpack_t5b1(bytes, &mut trits)?;
+    Ok(trits)
+}
+
+/// Encode binary data as trits, six trits per byte (B1T6).
+#[cfg(feature = "alloc")]
+pub fn bytes_to_trits(bytes: &[u8]) -> Vec<Trit> {
+    let mut trits = vec![0; bytes.len() * TRITS_PER_B1T6];
+    for (chunk, &byte) in trits.chunks_mut(TRITS_PER_B1T6).zip(bytes.iter()) {
+        // Any i8 fits in six balanced trits
+        write_value(i64::from(byte as i8), chunk);
+    }
+    trits
+}
+
+/// Decode B1T6 trits back into binary data.
+#[cfg(feature = "alloc")]
+pub fn trits_to_bytes(trits: &[Trit]) -> Result<Vec<u8>> {
+    check_multiple(trits.len(), TRITS_PER_B1T6)?;
+    let trits = TritSlice::new(trits)?;
+    trits
+        .as_trits()
+        .chunks(TRITS_PER_B1T6)
+        .enumerate()
+        .map(|(index, chunk)| {
+            trits_value(chunk)
+                .and_then(|value| i8::try_from(value).ok())
+                .map(|value| value as u8)
+                .ok_or(Error::InvalidEncoding {
+                    encoding: "B1T6",
+                    index,
+                })
+        })
+        .collect()
+}
+
+/// Interpret little-endian balanced trits as an integer.
+pub fn trits_to_i64(trits: &[Trit]) -> Result<i64> {
+    // Only a non-empty input can overflow
+    trits_value(TritSlice::new(trits)?.as_trits()).ok_or_else(|| Error::InvalidEncoding {
+        encoding: "i64",
+        index: trits.len() - 1,
+    })
+}
+
+/// Encode an integer as the shortest little-endian balanced trits.
+#[cfg(feature = "alloc")]
+pub fn i64_to_trits(value: i64) -> Vec<Trit> {
+    // Any i64 fits in 41 balanced trits
+    let mut trits = vec![0; 41];
+    write_value(value, &mut trits);
+    let length = trits
+        .iter()
+        .rposition(|&trit| trit != 0)
+        .map_or(0, |i| i + 1);
+    trits.truncate(length);
+    trits
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test_encoding {
+    use super::*;
+
+    #[test]
+    fn test_balanced() {
+        let trits = balanced_to_trits(&[-1, 0, 1, 1, -1]).unwrap();
+        assert_eq!(trits, vec![2, 0, 1, 1, 2]);
+        assert_eq!(trits_to_balanced(&trits).unwrap(), vec![-1, 0, 1, 1, -1]);
+
+        assert!(balanced_to_trits(&[2]).is_err());
+        assert!(trits_to_balanced(&[3]).is_err());
+    }
+
+    #[test]
+    fn test_trytes() {
+        let trytes = "9ABMNZ";
+        let trits = trytes_to_trits(trytes).unwrap();
+        assert_eq!(
+            trits,
+            vec![0, 0, 0, 1, 0, 0, 2, 1, 0, 1, 1, 1, 2, 2, 2, 2, 0, 0]
+        );
+        assert_eq!(trits_to_trytes(&trits).unwrap(), trytes);
+
+        assert_eq!(
+            trytes_to_trits("9a"),
+            Err(Error::InvalidEncoding {
+                encoding: "tryte",
+                index: 1
+            })
+        );
+        assert!(trytes_to_trits("Ä").is_err());
+        assert_eq!(
+            trits_to_trytes(&[0, 0]),
+            Err(Error::InvalidLength {
+                expected: 3,
+                actual: 2
+            })
+        );
+        assert_eq!(
+            trits_to_trytes(&[0, 0, 3]),
+            Err(Error::InvalidTrit { index: 2, value: 3 })
+        );
+    }
+
+    #[test]
+    fn test_t5b1() {
+        let trits = vec![1, 1, 1, 1, 1, 2, 2, 2, 2, 2, 0, 1];
+        let bytes = trits_to_t5b1(&trits).unwrap();
+        assert_eq!(bytes, vec![121, (-121i8) as u8, 3]);
+        assert_eq!(t5b1_to_trits(&bytes, trits.len()).unwrap(), trits);
+
+        assert_eq!(
+            t5b1_to_trits(&bytes, 16),
+            Err(Error::InvalidLength {
+                expected: 4,
+                actual: 3
+            })
+        );
+        assert!(t5b1_to_trits(&[122], 5).is_err());
+        assert!(t5b1_to_trits(&[81], 4).is_err());
+        assert!(trits_to_t5b1(&[5]).is_err());
+    }
+
+    #[test]
+    fn test_b1t6() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let trits = bytes_to_trits(&bytes);
+        assert_eq!(trits.len(), 6 * 256);
+        assert_eq!(trits_to_bytes(&trits).unwrap(), bytes);
+
+        assert!(trits_to_bytes(&[1; 5]).is_err());
+        assert!(trits_to_bytes(&[1; 6]).is_err());
+    }
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(i64_to_trits(0), Vec::<Trit>::new());
+        assert_eq!(i64_to_trits(5), vec![2, 2, 1]);
+        assert_eq!(i64_to_trits(-5), vec![1, 1, 2]);
+        for &value in [0, 1, -1, 13, -13, 1 << 40, i64::MAX, i64::MIN].iter() {
+            assert_eq!(trits_to_i64(&i64_to_trits(value)).unwrap(), value);
+        }
+
+        assert!(trits_to_i64(&[1; 41]).is_err());
+        assert!(trits_to_i64(&[3]).is_err());
+    }
+}
@@ -0,0 +1,52 @@
+use core::fmt;
+
+/// The Error enum lists the ways input to this crate can be rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// A round count outside of `1..=max`.
+    InvalidRounds { rounds: usize, max: usize },
+    /// A trit is not one of 0, 1 or 2.
+    InvalidTrit { index: usize, value: u8 },
+    /// The element at `index` is not valid in the named encoding.
+    InvalidEncoding {
+        encoding: &'static str,
+        index: usize,
+    },
+    /// An input of `actual` elements where `expected` were needed.
+    InvalidLength { expected: usize, actual: usize },
+    /// The selected backend is not supported by the running CPU.
+    UnsupportedBackend,
+    /// A minimum weight magnitude above `max`.
+    InvalidWeight { mwm: usize, max: usize },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidRounds { rounds, max } => {
+                write!(f, "invalid round count {}, expected 1 to {}", rounds, max)
+            }
+            Error::InvalidTrit { index, value } => {
+                write!(f, "invalid trit value {} at index {}", value, index)
+            }
+            Error::InvalidEncoding { encoding, index } => {
+                write!(f, "invalid {} encoding at index {}", encoding, index)
+            }
+            Error::InvalidLength { expected, actual } => {
+                write!(f, "invalid length {}, expected {}", actual, expected)
+            }
+            Error::UnsupportedBackend => write!(f, "backend not supported by this CPU"),
+            Error::InvalidWeight { mwm, max } => {
+                write!(
+                    f,
+                    "invalid weight magnitude {}, expected at most {}",
+                    mwm, max
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
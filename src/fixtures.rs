@@ -0,0 +1,23 @@
+//! Deterministic trits shared by the unit tests.
+
+use crate::constants::{Trit, STATE_SIZE};
+
+/// `N` trits of which the first `length` vary with `seed` and the rest
+/// are zero.
+pub(crate) fn trits<const N: usize>(length: usize, seed: usize) -> [Trit; N] {
+    let mut trits = [0; N];
+    for (i, trit) in trits[..length].iter_mut().enumerate() {
+        *trit = ((i * i + seed * 7 + i / 11) % 3) as Trit;
+    }
+    trits
+}
+
+/// A message buffer with its first `length` trits set.
+pub(crate) fn message<const N: usize>(length: usize) -> [Trit; N] {
+    trits(length, 0)
+}
+
+/// A full state that varies with `seed`.
+pub(crate) fn state(seed: usize) -> [Trit; STATE_SIZE] {
+    trits(STATE_SIZE, seed)
+}
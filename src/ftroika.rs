@@ -1,11 +1,72 @@
-#![allow(dead_code)]
-
 use super::constants::{
-    Trit, COLUMNS, FROUND_CONSTANTS, NUM_ROUNDS, ROWS, SLICES, SLICESIZE, TROIKA_RATE,
+    Trit, COLUMNS, FROUND_CONSTANTS, NUM_ROUNDS, PADDING, ROWS, SHIFT_LANES, SHIFT_ROWS,
+    SHIFT_ROWS_SOURCE, SLICES, SLICESIZE, STATE_SIZE, TROIKA_RATE,
+};
+use crate::encoding::{
+    check_t5b1, check_t5b1_length, check_trytes, masks_t5b1_byte, masks_tryte_byte, t5b1_masks,
+    tryte_masks, TritMasks, TRITS_PER_T5B1, TRITS_PER_TRYTE,
 };
-use crate::{Result, Sponge};
+use crate::hasher::StreamingSponge;
+use crate::params::{DefaultParams, Rounds, Tables};
+use crate::permutation::{check_rounds, Permutation};
+use crate::troika::Troika;
+use crate::{Error, Midstate, Result, Sponge};
 use core::fmt;
 
+#[cfg(target_arch = "x86_64")]
+mod simd;
+
+/// The FtroikaBackend enum selects how Ftroika computes its permutation.
+/// All backends give identical results.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FtroikaBackend {
+    /// Portable code on `u32` bit-planes.
+    Scalar,
+    /// x86_64 SSE2 vectors.
+    Sse2,
+    /// x86_64 AVX2 vectors.
+    Avx2,
+}
+
+impl FtroikaBackend {
+    /// The fastest backend supported by the running CPU.
+    pub fn detect() -> FtroikaBackend {
+        [FtroikaBackend::Avx2, FtroikaBackend::Sse2]
+            .iter()
+            .cloned()
+            .find(|backend| backend.is_supported())
+            .unwrap_or(FtroikaBackend::Scalar)
+    }
+
+    pub fn is_supported(self) -> bool {
+        match self {
+            FtroikaBackend::Scalar => true,
+            #[cfg(all(target_arch = "x86_64", feature = "std"))]
+            FtroikaBackend::Sse2 => std::is_x86_feature_detected!("sse2"),
+            #[cfg(all(target_arch = "x86_64", feature = "std"))]
+            FtroikaBackend::Avx2 => std::is_x86_feature_detected!("avx2"),
+            // Without std only the features enabled at compile time are known
+            #[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+            FtroikaBackend::Sse2 => cfg!(target_feature = "sse2"),
+            #[cfg(all(target_arch = "x86_64", not(feature = "std")))]
+            FtroikaBackend::Avx2 => cfg!(target_feature = "avx2"),
+            #[cfg(not(target_arch = "x86_64"))]
+            _ => false,
+        }
+    }
+}
+
+/// `1 << SHIFT_LANES[rowcol]`, for rotating with a multiplication.
+const SHIFT_LANES_FACTORS: [u64; SLICESIZE] = {
+    let mut factors = [0; SLICESIZE];
+    let mut rowcol = 0;
+    while rowcol < SLICESIZE {
+        factors[rowcol] = 1 << SHIFT_LANES[rowcol];
+        rowcol += 1;
+    }
+    factors
+};
+
 #[derive(Clone, Copy)]
 struct T27 {
     pub p: u32,
@@ -20,17 +81,19 @@ impl fmt::Debug for T27 {
 
 impl T27 {
     fn new(p: u32, n: u32) -> T27 {
-        T27 { p: p, n: n }
+        T27 { p, n }
     }
 
+    #[cfg(test)]
     fn clean(&self) -> T27 {
         T27::new(self.p & 0x07ffffffu32, self.n & 0x07ffffffu32)
     }
 
+    /// Clean inputs give a clean sum.
     fn add(&self, other: &T27) -> T27 {
-        let self_zero: u32 = !self.p & !self.n;
-        let p = !(self.n ^ other.n) & !(self_zero ^ other.p);
-        let n = !(self.p ^ other.p) & !(self_zero ^ other.n);
+        let t = (self.p | other.n) ^ (self.n | other.p);
+        let p = (self.n | other.n) ^ t;
+        let n = (self.p | other.p) ^ t;
         T27::new(p, n)
     }
 
@@ -44,26 +107,18 @@ impl T27 {
         T27::new(0, 0)
     }
 
-    fn one() -> T27 {
-        T27::new(0x07ffffffu32, 0)
-    }
-
     fn minus() -> T27 {
         T27::new(0, 0x07ffffffu32)
     }
 
     fn dec(&self) -> T27 {
-        T27::minus().add(&self)
-    }
-
-    fn inc(&self) -> T27 {
-        T27::one().add(&self)
+        T27::minus().add(self)
     }
 
     fn set(&mut self, pos: usize, value: Trit) {
         let mask: u32 = 1u32 << pos;
-        //self.p &= !mask;
-        //self.n &= !mask;
+        self.p &= !mask;
+        self.n &= !mask;
         match value {
             1 => self.p |= mask,
             2 => self.n |= mask,
@@ -71,7 +126,7 @@ impl T27 {
         }
     }
 
-    pub fn get(&mut self, pos: usize) -> Trit {
+    fn get(&self, pos: usize) -> Trit {
         let mask: u32 = 1u32 << pos;
         if self.p & mask != 0 {
             return 1;
@@ -81,6 +136,23 @@ impl T27 {
         0
     }
 
+    fn neg(&self) -> T27 {
+        T27::new(self.n, self.p)
+    }
+
+    /// Same as `roll(by)` for `factor = 1 << by`, without the shifts by
+    /// a variable amount: the product has the rotated out bits right
+    /// above the low 27.
+    #[inline(always)]
+    fn roll_by_factor(&self, factor: u64) -> T27 {
+        let p = u64::from(self.p) * factor;
+        let n = u64::from(self.n) * factor;
+        T27::new(
+            (p | p >> SLICES) as u32 & 0x07ffffff,
+            (n | n >> SLICES) as u32 & 0x07ffffff,
+        )
+    }
+
     fn roll(&self, by: usize) -> T27 {
         let p = ((self.p << by) | (self.p >> (27 - by))) & 0x07ffffff;
         let n = ((self.n << by) | (self.n >> (27 - by))) & 0x07ffffff;
@@ -92,7 +164,9 @@ impl T27 {
 /// hashing algorithm.
 #[derive(Clone, Copy)]
 pub struct Ftroika {
+    backend: FtroikaBackend,
     num_rounds: usize,
+    tables: &'static Tables,
     idx: usize,
     rowcol: usize,
     slice: usize,
@@ -102,7 +176,9 @@ pub struct Ftroika {
 impl Default for Ftroika {
     fn default() -> Ftroika {
         Ftroika {
+            backend: FtroikaBackend::detect(),
             num_rounds: NUM_ROUNDS,
+            tables: Tables::of::<DefaultParams>(),
             idx: 0,
             rowcol: 0,
             slice: 0,
@@ -115,8 +191,8 @@ impl fmt::Debug for Ftroika {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "Ftroika: [rounds: [{}], state: {:?}",
-            self.num_rounds, self.state,
+            "Ftroika: [backend: {:?}, rounds: [{}], state: {:?}",
+            self.backend, self.num_rounds, self.state,
         )
     }
 }
@@ -124,19 +200,41 @@ impl fmt::Debug for Ftroika {
 impl Sponge for Ftroika {
     fn absorb(&mut self, trits: &[Trit]) {
         self.absorb_sequence(trits);
-        let pad: [Trit; 1] = [1];
-        self.absorb_sequence(&pad);
-        if self.idx != 0 {
-            self.permutation();
-            self.reset_counters();
-        }
+        self.absorb_padding();
     }
 
     fn squeeze(&mut self, trits: &mut [Trit]) {
+        self.squeeze_with(trits, Ftroika::permutation);
+    }
+
+    fn reset(&mut self) {
+        self.state = [T27::zero(); SLICESIZE];
+        self.reset_counters();
+    }
+}
+
+impl StreamingSponge for Ftroika {
+    fn absorb_sequence(&mut self, trits: &[Trit]) {
+        self.absorb_sequence_with(trits, Ftroika::permutation);
+    }
+
+    fn absorb_padding(&mut self) {
+        self.absorb_padding_with(Ftroika::permutation);
+    }
+}
+
+// The sponge logic, shared with FixedFtroika, which brings its own permutation
+impl Ftroika {
+    fn squeeze_with(&mut self, trits: &mut [Trit], permutation: impl Fn(&mut Ftroika)) {
         let mut length = trits.len();
         let mut space;
         let mut trit_idx = 0;
         while length > 0 {
+            // Permute lazily, like Troika
+            if self.idx == TROIKA_RATE {
+                permutation(self);
+                self.reset_counters();
+            }
             space = TROIKA_RATE - self.idx;
             if length < space {
                 space = length;
@@ -151,31 +249,369 @@ impl Sponge for Ftroika {
                     self.slice += 1;
                 }
             }
-            //trit_idx += space;
+            length -= space;
+        }
+    }
+
+    fn absorb_sequence_with(&mut self, trits: &[Trit], permutation: impl Fn(&mut Ftroika)) {
+        let mut length = trits.len();
+        let mut space;
+        let mut trit_idx = 0;
+        while length > 0 {
+            if self.idx == 0 {
+                self.nullify_rate();
+            }
+            space = TROIKA_RATE - self.idx;
+            if length < space {
+                space = length;
+            }
+            for _ in 0..space {
+                self.set(trits[trit_idx]);
+                self.idx += 1;
+                self.rowcol += 1;
+                trit_idx += 1;
+                if self.rowcol == SLICESIZE {
+                    self.rowcol = 0;
+                    self.slice += 1;
+                }
+            }
             length -= space;
             if self.idx == TROIKA_RATE {
-                self.permutation();
+                permutation(self);
                 self.reset_counters();
             }
         }
     }
 
-    fn reset(&mut self) {
-        self.state = [T27::zero(); SLICESIZE];
-        self.reset_counters();
+    fn absorb_padding_with(&mut self, permutation: impl Fn(&mut Ftroika)) {
+        self.absorb_sequence_with(&[PADDING], &permutation);
+        if self.idx != 0 {
+            permutation(self);
+            self.reset_counters();
+        }
+    }
+
+    fn absorb_packed_with(
+        &mut self,
+        bytes: &[u8],
+        num_trits: usize,
+        permutation: impl Fn(&mut Ftroika),
+    ) -> Result<()> {
+        check_t5b1(bytes, num_trits)?;
+        let masks = bytes.iter().enumerate().map(|(index, &byte)| {
+            let used = (num_trits - TRITS_PER_T5B1 * index).min(TRITS_PER_T5B1);
+            (t5b1_masks(byte), used)
+        });
+        self.absorb_masks_with(masks, &permutation);
+        Ok(())
+    }
+
+    fn absorb_trytes_with(
+        &mut self,
+        trytes: &str,
+        permutation: impl Fn(&mut Ftroika),
+    ) -> Result<()> {
+        check_trytes(trytes)?;
+        let masks = trytes.bytes().map(|c| (tryte_masks(c), TRITS_PER_TRYTE));
+        self.absorb_masks_with(masks, &permutation);
+        Ok(())
+    }
+
+    fn squeeze_packed_with(
+        &mut self,
+        bytes: &mut [u8],
+        num_trits: usize,
+        permutation: impl Fn(&mut Ftroika),
+    ) -> Result<()> {
+        check_t5b1_length(bytes.len(), num_trits)?;
+        let counts =
+            (0..bytes.len()).map(|index| (num_trits - TRITS_PER_T5B1 * index).min(TRITS_PER_T5B1));
+        self.squeeze_masks_with(bytes, counts, masks_t5b1_byte, &permutation);
+        Ok(())
+    }
+
+    fn squeeze_trytes_with(&mut self, trytes: &mut [u8], permutation: impl Fn(&mut Ftroika)) {
+        let counts = (0..trytes.len()).map(|_| TRITS_PER_TRYTE);
+        self.squeeze_masks_with(trytes, counts, masks_tryte_byte, &permutation);
+    }
+
+    /// Absorb trits given as masks with their counts. Consecutive trits
+    /// are in consecutive rowcols of the same slice, so the masks are
+    /// gathered into one run per slice, which is ORed into the planes of
+    /// the cleared rate in a single pass.
+    #[inline(always)]
+    fn absorb_masks_with(
+        &mut self,
+        masks: impl Iterator<Item = (TritMasks, usize)>,
+        permutation: &impl Fn(&mut Ftroika),
+    ) {
+        let (mut p, mut n, mut pending) = (0u128, 0u128, 0);
+        for ([p_mask, n_mask], count) in masks {
+            p |= u128::from(p_mask) << pending;
+            n |= u128::from(n_mask) << pending;
+            pending += count;
+            while pending >= SLICESIZE - self.rowcol {
+                let run = SLICESIZE - self.rowcol;
+                self.absorb_run(p, n, run, permutation);
+                p >>= run;
+                n >>= run;
+                pending -= run;
+            }
+        }
+        if pending > 0 {
+            self.absorb_run(p, n, pending, permutation);
+        }
+    }
+
+    /// OR a run of `len` trits into the current slice from `rowcol` on.
+    #[inline(always)]
+    fn absorb_run(&mut self, p: u128, n: u128, len: usize, permutation: &impl Fn(&mut Ftroika)) {
+        // A fully squeezed block, see squeeze_with
+        if self.idx == TROIKA_RATE {
+            permutation(self);
+            self.reset_counters();
+        }
+        if self.idx == 0 {
+            self.nullify_rate();
+        }
+        // Overwrite, the block may have been partly squeezed
+        let (mut p, mut n, slice) = (p, n, self.slice);
+        let keep = !(1u32 << slice);
+        for t27 in self.state[self.rowcol..self.rowcol + len].iter_mut() {
+            t27.p = t27.p & keep | (p as u32 & 1) << slice;
+            t27.n = t27.n & keep | (n as u32 & 1) << slice;
+            p >>= 1;
+            n >>= 1;
+        }
+        self.advance(len);
+        if self.idx == TROIKA_RATE {
+            permutation(self);
+            self.reset_counters();
+        }
+    }
+
+    /// Squeeze the given number of trits into each of `out`, reading the
+    /// rate planes one run per slice like `absorb_masks_with` writes them.
+    #[inline(always)]
+    fn squeeze_masks_with(
+        &mut self,
+        out: &mut [u8],
+        counts: impl Iterator<Item = usize> + Clone,
+        encode: fn(TritMasks) -> u8,
+        permutation: &impl Fn(&mut Ftroika),
+    ) {
+        let mut unread: usize = counts.clone().sum();
+        let (mut p, mut n, mut ready) = (0u128, 0u128, 0);
+        for (byte, count) in out.iter_mut().zip(counts) {
+            while ready < count {
+                if self.idx == TROIKA_RATE {
+                    permutation(self);
+                    self.reset_counters();
+                }
+                let run = (SLICESIZE - self.rowcol).min(unread);
+                let (run_p, run_n) = self.squeeze_run(run);
+                p |= run_p << ready;
+                n |= run_n << ready;
+                ready += run;
+                unread -= run;
+            }
+            let mask = (1 << count) - 1;
+            *byte = encode([p as u8 & mask, n as u8 & mask]);
+            p >>= count;
+            n >>= count;
+            ready -= count;
+        }
+    }
+
+    /// Read a run of `len` trits of the current slice from `rowcol` on.
+    #[inline(always)]
+    fn squeeze_run(&mut self, len: usize) -> (u128, u128) {
+        let (mut p, mut n, slice) = (0u128, 0u128, self.slice);
+        for t27 in self.state[self.rowcol..self.rowcol + len].iter().rev() {
+            p = p << 1 | u128::from(t27.p >> slice & 1);
+            n = n << 1 | u128::from(t27.n >> slice & 1);
+        }
+        self.advance(len);
+        (p, n)
+    }
+
+    /// Move `by` trits on within the current slice.
+    fn advance(&mut self, by: usize) {
+        self.idx += by;
+        self.rowcol += by;
+        if self.rowcol == SLICESIZE {
+            self.rowcol = 0;
+            self.slice += 1;
+        }
     }
 }
 
-impl Ftroika {
+impl From<Troika> for Ftroika {
+    /// Move the whole sponge, including its position in the rate,
+    /// to the bit-plane layout.
+    fn from(troika: Troika) -> Ftroika {
+        let mut ftroika = Ftroika::default();
+        ftroika.restore(&troika.midstate());
+        ftroika
+    }
+}
+
+impl Permutation for Ftroika {
+    fn permute_unchecked(state: &mut [Trit; STATE_SIZE], num_rounds: usize) {
+        let mut ftroika = Ftroika {
+            num_rounds,
+            ..Ftroika::default()
+        };
+        ftroika.load_state(state);
+        ftroika.permutation();
+        ftroika.store_state(state);
+    }
+
+    fn inverse_unchecked(state: &mut [Trit; STATE_SIZE], num_rounds: usize) {
+        let mut ftroika = Ftroika {
+            num_rounds,
+            ..Ftroika::default()
+        };
+        ftroika.load_state(state);
+        ftroika.inverse_rounds();
+        ftroika.store_state(state);
+    }
+}
+
+#[cfg(feature = "research")]
+impl crate::research::RoundSteps for Ftroika {
+    fn from_state(state: &[Trit; STATE_SIZE]) -> Ftroika {
+        let mut ftroika = Ftroika::default();
+        ftroika.load_state(state);
+        ftroika
+    }
+
+    fn to_state(&self) -> [Trit; STATE_SIZE] {
+        let mut state = [0; STATE_SIZE];
+        self.store_state(&mut state);
+        state
+    }
+
+    fn sub_trytes(&mut self) {
+        Ftroika::sub_trytes(self);
+    }
+
+    fn shift_rows(&mut self) {
+        Ftroika::shift_rows(self);
+    }
+
+    fn shift_lanes(&mut self) {
+        Ftroika::shift_lanes(self);
+    }
+
+    fn add_column_parity(&mut self) {
+        Ftroika::add_column_parity(self);
+    }
+
+    fn add_round_constant(&mut self, round: usize) {
+        Ftroika::add_round_constant(self, round);
+    }
 
+    fn inverse_sub_trytes(&mut self) {
+        Ftroika::inverse_sub_trytes(self);
+    }
+
+    fn inverse_shift_rows_lanes(&mut self) {
+        Ftroika::inverse_shift_lanes(self);
+        Ftroika::inverse_shift_rows(self);
+    }
+
+    fn inverse_add_column_parity(&mut self) {
+        Ftroika::inverse_add_column_parity(self);
+    }
+
+    fn inverse_add_round_constant(&mut self, round: usize) {
+        Ftroika::inverse_add_round_constant(self, round);
+    }
+}
+
+impl Ftroika {
+    /// Create a sponge with a reduced number of rounds.
+    /// Fails unless `num_rounds` is in `1..=NUM_ROUNDS`.
     pub fn new(num_rounds: usize) -> Result<Ftroika> {
-        let mut troika = Ftroika::default();
-        troika.num_rounds = num_rounds;
-        Ok(troika)
+        check_rounds(num_rounds)?;
+        Ok(Ftroika {
+            num_rounds,
+            ..Ftroika::default()
+        })
     }
 
-    fn state(&self) -> &[T27] {
-        &self.state
+    /// Create a sponge that uses the given backend for its permutation.
+    /// Fails if the running CPU does not support it.
+    pub fn with_backend(backend: FtroikaBackend) -> Result<Ftroika> {
+        if !backend.is_supported() {
+            return Err(Error::UnsupportedBackend);
+        }
+        Ok(Ftroika {
+            backend,
+            ..Ftroika::default()
+        })
+    }
+
+    /// Switch to the round count and tables of a `TroikaParams`.
+    pub(crate) fn set_params(&mut self, num_rounds: usize, tables: &'static Tables) {
+        self.num_rounds = num_rounds;
+        self.tables = tables;
+    }
+
+    pub fn backend(&self) -> FtroikaBackend {
+        self.backend
+    }
+
+    /// Take a snapshot of the sponge.
+    pub fn midstate(&self) -> Midstate {
+        let mut state = [0; STATE_SIZE];
+        self.store_state(&mut state);
+        Midstate::new(self.num_rounds, self.idx, state)
+    }
+
+    /// Continue from a snapshot taken on any backend. The sponge keeps
+    /// its backend.
+    pub fn restore(&mut self, midstate: &Midstate) {
+        self.num_rounds = midstate.num_rounds();
+        self.state = [T27::zero(); SLICESIZE];
+        self.load_state(midstate.state());
+        self.idx = midstate.idx();
+        self.rowcol = self.idx % SLICESIZE;
+        self.slice = self.idx / SLICESIZE;
+    }
+
+    /// Absorb `num_trits` trits packed five per byte (T5B1), the same as
+    /// `absorb_sequence` on the unpacked trits. Nothing is absorbed if
+    /// `bytes` is not the encoding of exactly `num_trits` trits.
+    pub fn absorb_packed(&mut self, bytes: &[u8], num_trits: usize) -> Result<()> {
+        self.absorb_packed_with(bytes, num_trits, Ftroika::permutation)
+    }
+
+    /// Absorb the trits of a tryte string, the same as `absorb_sequence`
+    /// on the unpacked trits. Nothing is absorbed if a character is not
+    /// in the tryte alphabet.
+    pub fn absorb_trytes(&mut self, trytes: &str) -> Result<()> {
+        self.absorb_trytes_with(trytes, Ftroika::permutation)
+    }
+
+    /// Squeeze `num_trits` trits packed five per byte (T5B1) into
+    /// `bytes`, which must hold exactly enough bytes.
+    pub fn squeeze_packed(&mut self, bytes: &mut [u8], num_trits: usize) -> Result<()> {
+        self.squeeze_packed_with(bytes, num_trits, Ftroika::permutation)
+    }
+
+    /// Squeeze one tryte character into every byte of `trytes`.
+    pub fn squeeze_trytes(&mut self, trytes: &mut [u8]) {
+        self.squeeze_trytes_with(trytes, Ftroika::permutation);
+    }
+
+    /// The current rate, read without squeezing.
+    #[cfg(feature = "std")]
+    pub(crate) fn rate(&self) -> [Trit; TROIKA_RATE] {
+        let mut rate = [0; TROIKA_RATE];
+        self.store_state(&mut rate);
+        rate
     }
 
     fn reset_counters(&mut self) {
@@ -192,6 +628,20 @@ impl Ftroika {
         self.state[self.rowcol].get(self.slice)
     }
 
+    /// Set the bit-planes from a flat state. Trits are spread over the
+    /// rowcol index first, then over the slices.
+    fn load_state(&mut self, state: &[Trit]) {
+        for (idx, &trit) in state.iter().enumerate() {
+            self.state[idx % SLICESIZE].set(idx / SLICESIZE, trit);
+        }
+    }
+
+    fn store_state(&self, state: &mut [Trit]) {
+        for (idx, trit) in state.iter_mut().enumerate() {
+            *trit = self.state[idx % SLICESIZE].get(idx / SLICESIZE);
+        }
+    }
+
     fn nullify_rate(&mut self) {
         let mask = 0x07fffe00u32;
         for i in 0..SLICESIZE {
@@ -200,45 +650,64 @@ impl Ftroika {
         }
     }
 
-    fn absorb_sequence(&mut self, trits: &[Trit]) {
-        let mut length = trits.len();
-        let mut space;
-        let mut trit_idx = 0;
-        while length > 0 {
-            if self.idx == 0 {
-                self.nullify_rate();
-            }
-            space = TROIKA_RATE - self.idx;
-            if length < space {
-                space = length;
-            }
-            for _ in 0..space {
-                self.set(trits[trit_idx]);
-                self.idx += 1;
-                self.rowcol += 1;
-                trit_idx += 1;
-                if self.rowcol == SLICESIZE {
-                    self.rowcol = 0;
-                    self.slice += 1;
-                }
-            }
-            length -= space;
-            if self.idx == TROIKA_RATE {
-                self.permutation();
-                self.reset_counters();
-            }
-        }
-    }
-
     fn permutation(&mut self) {
         assert!(self.num_rounds <= NUM_ROUNDS);
 
-        for round in 0..self.num_rounds {
+        // The vector code only has the bitsliced formula of the default S-box
+        if !self.tables.default_sbox {
+            return self.scalar_permutation();
+        }
+        let constants = &self.tables.fround_constants;
+        // The backend was checked to be supported when it was selected
+        match self.backend {
+            #[cfg(target_arch = "x86_64")]
+            FtroikaBackend::Avx2 => unsafe {
+                simd::permutation_avx2(&mut self.state, self.num_rounds, constants)
+            },
+            #[cfg(target_arch = "x86_64")]
+            FtroikaBackend::Sse2 => unsafe {
+                simd::permutation_sse2(&mut self.state, self.num_rounds, constants)
+            },
+            _ => self.scalar_permutation(),
+        }
+    }
+
+    fn scalar_permutation(&mut self) {
+        let tables = self.tables;
+        for constants in tables.fround_constants[..self.num_rounds].iter() {
             self.sub_trytes();
-            self.shift_rows();
-            self.shift_lanes();
-            self.add_column_parity();
-            self.add_round_constant(round);
+            self.shift_and_mix(constants);
+        }
+    }
+
+    /// `ROUNDS` rounds of standard Troika, unrolled on the scalar backend.
+    fn fixed_permutation<const ROUNDS: usize>(&mut self) {
+        // The backend was checked to be supported when it was selected
+        match self.backend {
+            #[cfg(target_arch = "x86_64")]
+            FtroikaBackend::Avx2 => unsafe {
+                simd::fixed_permutation_avx2::<ROUNDS>(&mut self.state)
+            },
+            #[cfg(target_arch = "x86_64")]
+            FtroikaBackend::Sse2 => unsafe {
+                simd::fixed_permutation_sse2::<ROUNDS>(&mut self.state)
+            },
+            _ => unroll_rounds!(ROUNDS, |round| {
+                self.bitsliced_sub_trytes();
+                self.shift_and_mix(&FROUND_CONSTANTS[round]);
+            }),
+        }
+    }
+
+    /// Undo the first `num_rounds` rounds, last round first. There are
+    /// no SIMD versions of the inverse steps.
+    fn inverse_rounds(&mut self) {
+        for round in (0..self.num_rounds).rev() {
+            self.inverse_add_round_constant(round);
+            self.inverse_add_column_parity();
+            self.inverse_shift_lanes();
+            self.inverse_shift_rows();
+            self.inverse_sub_trytes();
         }
     }
 
@@ -247,72 +716,255 @@ impl Ftroika {
         let e = d.mul(&a[1]).add(&a[2]);
         let f = e.mul(&a[1]).add(&d);
         let g = e.mul(&f).add(&a[1]);
-        a[2] = e.clean();
-        a[1] = f.clean();
-        a[0] = g.clean();
+        a[2] = e;
+        a[1] = f;
+        a[0] = g;
     }
 
     fn sub_trytes(&mut self) {
+        if !self.tables.default_sbox {
+            return self.substitute(&self.tables.sbox);
+        }
+        self.bitsliced_sub_trytes();
+    }
+
+    fn bitsliced_sub_trytes(&mut self) {
+        for tryte in self.state.chunks_exact_mut(3) {
+            Ftroika::sub_tryte(tryte);
+        }
+    }
+
+    /// The rest of a round after the S-boxes, in one pass: every rowcol
+    /// is gathered from its source before shift_rows and rotated by its
+    /// shift_lanes amount, and each column sums its parity while its
+    /// three rowcols are at hand. The parity sums and `constants` are
+    /// then added on the way back into the state.
+    #[inline(always)]
+    fn shift_and_mix(&mut self, constants: &[[u32; 2]; COLUMNS]) {
+        let mut shifted = [[T27::zero(); ROWS]; COLUMNS];
+        let mut parity = [T27::zero(); COLUMNS];
+        for (col, (rows, col_sum)) in shifted.iter_mut().zip(parity.iter_mut()).enumerate() {
+            for (row, t27) in rows.iter_mut().enumerate() {
+                let rowcol = COLUMNS * row + col;
+                *t27 = self.state[usize::from(SHIFT_ROWS_SOURCE[rowcol])]
+                    .roll_by_factor(SHIFT_LANES_FACTORS[rowcol]);
+            }
+            *col_sum = rows[0].add(&rows[1]).add(&rows[2]);
+        }
+
+        for (col, (rows, &[p, n])) in shifted.iter().zip(constants.iter()).enumerate() {
+            let left = parity[(col + COLUMNS - 1) % COLUMNS];
+            let right = parity[(col + 1) % COLUMNS].roll(SLICES - 1);
+            let sum = left.add(&right);
+            self.state[col] = rows[0].add(&sum.add(&T27::new(p, n)));
+            self.state[COLUMNS + col] = rows[1].add(&sum);
+            self.state[2 * COLUMNS + col] = rows[2].add(&sum);
+        }
+    }
+
+    fn inverse_sub_trytes(&mut self) {
+        self.substitute(&self.tables.inv_sbox);
+    }
+
+    /// Look up every tryte of every slice, for S-boxes without a
+    /// bitsliced formula.
+    fn substitute(&mut self, sbox: &[Trit; 27]) {
+        let mut new_state = [T27::zero(); SLICESIZE];
         for rowcol in (0..SLICESIZE).step_by(3) {
-            Ftroika::sub_tryte(&mut self.state[rowcol..(rowcol + 3)]);
+            for slice in 0..SLICES {
+                let input = 9 * self.state[rowcol].get(slice)
+                    + 3 * self.state[rowcol + 1].get(slice)
+                    + self.state[rowcol + 2].get(slice);
+                let output = sbox[input as usize];
+                new_state[rowcol].set(slice, output / 9);
+                new_state[rowcol + 1].set(slice, output / 3 % 3);
+                new_state[rowcol + 2].set(slice, output % 3);
+            }
         }
+        self.state = new_state;
     }
 
+    #[cfg(any(test, feature = "research"))]
     fn shift_rows(&mut self) {
-        const SHIFTS: [u8; 27] = [
-            0, 1, 2, 3, 4, 5, 6, 7, 8, 12, 13, 14, 15, 16, 17, 9, 10, 11, 24, 25, 26, 18, 19, 20,
-            21, 22, 23,
-        ];
         let mut new_state = [T27::zero(); SLICESIZE];
         for i in 0..SLICESIZE {
-            new_state[SHIFTS[i] as usize] = self.state[i];
+            new_state[SHIFT_ROWS[i] as usize] = self.state[i];
+        }
+        self.state = new_state;
+    }
+
+    fn inverse_shift_rows(&mut self) {
+        let mut new_state = [T27::zero(); SLICESIZE];
+        for (t27, &rowcol) in new_state.iter_mut().zip(SHIFT_ROWS.iter()) {
+            *t27 = self.state[rowcol as usize];
         }
         self.state = new_state;
     }
 
+    #[cfg(any(test, feature = "research"))]
     fn shift_lanes(&mut self) {
-        const SHIFTS: [u8; 27] = [
-            19, 13, 21, 10, 24, 15, 2, 9, 3, 14, 0, 6, 5, 1, 25, 22, 23, 20, 7, 17, 26, 12, 8, 18,
-            16, 11, 4,
-        ];
         let mut new_state = [T27::zero(); SLICESIZE];
         for i in 0..SLICESIZE {
-            new_state[i as usize] = self.state[i].roll(SHIFTS[i] as usize);
+            new_state[i] = self.state[i].roll(SHIFT_LANES[i] as usize);
         }
         self.state = new_state;
     }
 
+    fn inverse_shift_lanes(&mut self) {
+        for (t27, &by) in self.state.iter_mut().zip(SHIFT_LANES.iter()) {
+            *t27 = t27.roll((SLICES - by as usize) % SLICES);
+        }
+    }
+
+    #[cfg(any(test, feature = "research"))]
     fn add_column_parity(&mut self) {
+        let sums = self.column_parity_sums();
+        for (idx, t27) in self.state.iter_mut().enumerate() {
+            *t27 = t27.add(&sums[idx % COLUMNS]);
+        }
+    }
+
+    fn inverse_add_column_parity(&mut self) {
+        // Why this undoes the mixing: see Troika::inverse_add_column_parity
+        let sums = self.column_parity_sums();
+        for (idx, t27) in self.state.iter_mut().enumerate() {
+            *t27 = t27.add(&sums[idx % COLUMNS].neg());
+        }
+    }
+
+    /// The sums of the parities of the neighbouring columns, which get
+    /// added to every trit of each column.
+    fn column_parity_sums(&self) -> [T27; COLUMNS] {
         let mut parity = [T27::zero(); COLUMNS];
-        for col in 0..COLUMNS {
-            let mut col_sum = T27::zero();
+        for (col, col_sum) in parity.iter_mut().enumerate() {
             for row in 0..ROWS {
-                col_sum = col_sum.add(&self.state[COLUMNS * row + col]);
-            }
-            parity[col] = col_sum;
-        }
-        for row in 0..ROWS {
-            for col in 0..COLUMNS {
-                let idx = COLUMNS * row + col;
-                let t1 = parity[if col == 0 { COLUMNS - 1 } else { col - 1 }];
-                let t2 = parity[if col == COLUMNS - 1 { 0 } else { col + 1 }].roll(SLICES - 1);
-                let sum_to_add = t1.add(&t2);
-                self.state[idx] = self.state[idx].add(&sum_to_add);
+                *col_sum = col_sum.add(&self.state[COLUMNS * row + col]);
             }
         }
+        let mut sums = [T27::zero(); COLUMNS];
+        for (col, sum) in sums.iter_mut().enumerate() {
+            let t1 = parity[if col == 0 { COLUMNS - 1 } else { col - 1 }];
+            let t2 = parity[if col == COLUMNS - 1 { 0 } else { col + 1 }].roll(SLICES - 1);
+            *sum = t1.add(&t2);
+        }
+        sums
     }
 
-
+    #[cfg(any(test, feature = "research"))]
     fn add_round_constant(&mut self, round: usize) {
-        for col in 0..COLUMNS {
-            let round_const = T27::new(
-                FROUND_CONSTANTS[round][col][0],
-                FROUND_CONSTANTS[round][col][1],
-            );
-            //let bla = self.state[col];
-            self.state[col] = self.state[col].add(&round_const);
+        self.add_constants(&self.tables.fround_constants[round]);
+    }
+
+    #[cfg(any(test, feature = "research"))]
+    fn add_constants(&mut self, constants: &[[u32; 2]; COLUMNS]) {
+        for (col, &[p, n]) in constants.iter().enumerate() {
+            self.state[col] = self.state[col].add(&T27::new(p, n));
         }
     }
+
+    fn inverse_add_round_constant(&mut self, round: usize) {
+        for (col, &[p, n]) in self.tables.fround_constants[round].iter().enumerate() {
+            self.state[col] = self.state[col].add(&T27::new(p, n).neg());
+        }
+    }
+}
+
+/// The FixedFtroika struct is an Ftroika sponge whose round count is
+/// part of its type, so an invalid count fails to compile. The round
+/// count and constants are compile-time constants of its permutation,
+/// and the scalar backend unrolls its rounds.
+#[derive(Clone, Copy, Debug)]
+pub struct FixedFtroika<const ROUNDS: usize> {
+    ftroika: Ftroika,
+}
+
+/// Standard Ftroika with a compile-time round count.
+pub type Ftroika24 = FixedFtroika<NUM_ROUNDS>;
+
+impl<const ROUNDS: usize> Default for FixedFtroika<ROUNDS> {
+    fn default() -> FixedFtroika<ROUNDS> {
+        FixedFtroika::from_ftroika(Ftroika::default())
+    }
+}
+
+impl<const ROUNDS: usize> FixedFtroika<ROUNDS> {
+    /// Create a sponge that uses the given backend for its permutation.
+    /// Fails if the running CPU does not support it.
+    pub fn with_backend(backend: FtroikaBackend) -> Result<FixedFtroika<ROUNDS>> {
+        Ftroika::with_backend(backend).map(FixedFtroika::from_ftroika)
+    }
+
+    fn from_ftroika(mut ftroika: Ftroika) -> FixedFtroika<ROUNDS> {
+        ftroika.set_params(ROUNDS, Tables::of::<Rounds<ROUNDS>>());
+        FixedFtroika { ftroika }
+    }
+
+    pub fn backend(&self) -> FtroikaBackend {
+        self.ftroika.backend()
+    }
+
+    /// Take a snapshot of the sponge.
+    pub fn midstate(&self) -> Midstate {
+        self.ftroika.midstate()
+    }
+
+    /// See `Ftroika::rate`.
+    #[cfg(feature = "std")]
+    pub(crate) fn rate(&self) -> [Trit; TROIKA_RATE] {
+        self.ftroika.rate()
+    }
+
+    /// See `Ftroika::absorb_packed`.
+    pub fn absorb_packed(&mut self, bytes: &[u8], num_trits: usize) -> Result<()> {
+        self.ftroika
+            .absorb_packed_with(bytes, num_trits, Ftroika::fixed_permutation::<ROUNDS>)
+    }
+
+    /// See `Ftroika::absorb_trytes`.
+    pub fn absorb_trytes(&mut self, trytes: &str) -> Result<()> {
+        self.ftroika
+            .absorb_trytes_with(trytes, Ftroika::fixed_permutation::<ROUNDS>)
+    }
+
+    /// See `Ftroika::squeeze_packed`.
+    pub fn squeeze_packed(&mut self, bytes: &mut [u8], num_trits: usize) -> Result<()> {
+        self.ftroika
+            .squeeze_packed_with(bytes, num_trits, Ftroika::fixed_permutation::<ROUNDS>)
+    }
+
+    /// See `Ftroika::squeeze_trytes`.
+    pub fn squeeze_trytes(&mut self, trytes: &mut [u8]) {
+        self.ftroika
+            .squeeze_trytes_with(trytes, Ftroika::fixed_permutation::<ROUNDS>);
+    }
+}
+
+impl<const ROUNDS: usize> Sponge for FixedFtroika<ROUNDS> {
+    fn absorb(&mut self, trits: &[Trit]) {
+        self.absorb_sequence(trits);
+        self.absorb_padding();
+    }
+
+    fn squeeze(&mut self, trits: &mut [Trit]) {
+        self.ftroika
+            .squeeze_with(trits, Ftroika::fixed_permutation::<ROUNDS>);
+    }
+
+    fn reset(&mut self) {
+        self.ftroika.reset();
+    }
+}
+
+impl<const ROUNDS: usize> StreamingSponge for FixedFtroika<ROUNDS> {
+    fn absorb_sequence(&mut self, trits: &[Trit]) {
+        self.ftroika
+            .absorb_sequence_with(trits, Ftroika::fixed_permutation::<ROUNDS>);
+    }
+
+    fn absorb_padding(&mut self) {
+        self.ftroika
+            .absorb_padding_with(Ftroika::fixed_permutation::<ROUNDS>);
+    }
 }
 
 #[cfg(test)]
@@ -344,4 +996,314 @@ mod test_ftroika {
             "Arrays are not equal"
         );
     }
+
+    #[test]
+    fn test_lazy_permutation() {
+        let count = core::cell::Cell::new(0);
+        let permutation = |ftroika: &mut Ftroika| {
+            count.set(count.get() + 1);
+            ftroika.permutation();
+        };
+        let mut ftroika = Ftroika::default();
+        let mut output = [0u8; 243];
+        ftroika.absorb_sequence_with(&[0; 243], permutation);
+        ftroika.absorb_padding_with(permutation);
+        ftroika.squeeze_with(&mut output, permutation);
+        assert_eq!(output[..], HASH[..]);
+        assert_eq!(count.get(), 2);
+        ftroika.squeeze_with(&mut output[..1], permutation);
+        assert_eq!(count.get(), 3);
+    }
+
+    #[test]
+    fn test_backends() {
+        let mut input = [0u8; 1000];
+        for (i, trit) in input.iter_mut().enumerate() {
+            *trit = ((i * i + i / 7) % 3) as Trit;
+        }
+        let mut expected = [0u8; 500];
+        let mut scalar = Ftroika::with_backend(FtroikaBackend::Scalar).unwrap();
+        scalar.absorb(&input);
+        scalar.squeeze(&mut expected);
+
+        for &backend in [FtroikaBackend::Sse2, FtroikaBackend::Avx2].iter() {
+            if !backend.is_supported() {
+                assert_eq!(
+                    Ftroika::with_backend(backend).unwrap_err(),
+                    Error::UnsupportedBackend
+                );
+                continue;
+            }
+            let mut ftroika = Ftroika::with_backend(backend).unwrap();
+            assert_eq!(ftroika.backend(), backend);
+            for num_rounds in 1..=NUM_ROUNDS {
+                let mut state = [T27::zero(); SLICESIZE];
+                for (rowcol, t27) in state.iter_mut().enumerate() {
+                    *t27 = T27::new(0x0123_4567 * rowcol as u32, 0x0765_4321 ^ (rowcol as u32))
+                        .clean();
+                    t27.n &= !t27.p;
+                }
+                ftroika.num_rounds = num_rounds;
+                ftroika.state = state;
+                ftroika.permutation();
+                scalar.num_rounds = num_rounds;
+                scalar.state = state;
+                scalar.scalar_permutation();
+                for (a, b) in ftroika.state.iter().zip(scalar.state.iter()) {
+                    assert_eq!(
+                        (a.p, a.n),
+                        (b.p, b.n),
+                        "{:?} {} rounds",
+                        backend,
+                        num_rounds
+                    );
+                }
+            }
+
+            let mut output = [0u8; 500];
+            let mut ftroika = Ftroika::with_backend(backend).unwrap();
+            ftroika.absorb(&input);
+            ftroika.squeeze(&mut output);
+            assert_eq!(output[..], expected[..], "{:?}", backend);
+        }
+    }
+
+    fn assert_same_state(troika: &Troika, ftroika: &Ftroika, step: &str, round: usize) {
+        assert_eq!(
+            troika.state(),
+            Troika::from(*ftroika).state(),
+            "{} in round {}",
+            step,
+            round
+        );
+    }
+
+    #[test]
+    fn test_steps_match_reference() {
+        let mut state = [0; STATE_SIZE];
+        for (i, trit) in state.iter_mut().enumerate() {
+            *trit = ((i * i + i / 5) % 3) as Trit;
+        }
+        let mut troika = Troika::default();
+        troika.restore(&Midstate::new(NUM_ROUNDS, 0, state));
+        let mut ftroika = Ftroika::with_backend(FtroikaBackend::Scalar).unwrap();
+        ftroika.restore(&troika.midstate());
+        assert_same_state(&troika, &ftroika, "conversion", 0);
+
+        for (round, constants) in FROUND_CONSTANTS.iter().enumerate() {
+            let mut fused = ftroika;
+            troika.sub_trytes();
+            ftroika.sub_trytes();
+            assert_same_state(&troika, &ftroika, "sub_trytes", round);
+            troika.shift_rows_lanes();
+            ftroika.shift_rows();
+            ftroika.shift_lanes();
+            assert_same_state(&troika, &ftroika, "shift_rows_lanes", round);
+            troika.add_column_parity();
+            ftroika.add_column_parity();
+            assert_same_state(&troika, &ftroika, "add_column_parity", round);
+            troika.add_round_constant(round);
+            ftroika.add_round_constant(round);
+            assert_same_state(&troika, &ftroika, "add_round_constant", round);
+            fused.sub_trytes();
+            fused.shift_and_mix(constants);
+            assert_same_state(&troika, &fused, "shift_and_mix", round);
+        }
+    }
+
+    #[test]
+    fn test_convert_sponge() {
+        let mut input = [0u8; 400];
+        for (i, trit) in input.iter_mut().enumerate() {
+            *trit = ((i + i / 3) % 3) as Trit;
+        }
+        let mut ftroika = Ftroika::new(7).unwrap();
+        ftroika.absorb_sequence(&input);
+        let troika = Troika::from(ftroika);
+        assert_eq!(troika.midstate(), ftroika.midstate());
+        assert_eq!(Ftroika::from(troika).midstate(), ftroika.midstate());
+
+        let mut troika = troika;
+        troika.absorb_padding();
+        ftroika.absorb_padding();
+        let mut output = [0u8; 300];
+        let mut foutput = [0u8; 300];
+        troika.squeeze(&mut output[..100]);
+        ftroika.squeeze(&mut foutput[..100]);
+        Ftroika::from(troika).squeeze(&mut output[100..]);
+        Troika::from(ftroika).squeeze(&mut foutput[100..]);
+        assert_eq!(output[..], foutput[..]);
+    }
+
+    #[test]
+    fn test_new() {
+        assert!(Ftroika::new(1).is_ok());
+        assert!(Ftroika::new(NUM_ROUNDS).is_ok());
+        assert_eq!(
+            Ftroika::new(0).unwrap_err(),
+            Error::InvalidRounds {
+                rounds: 0,
+                max: NUM_ROUNDS
+            }
+        );
+        assert_eq!(
+            Ftroika::new(NUM_ROUNDS + 1).unwrap_err(),
+            Error::InvalidRounds {
+                rounds: NUM_ROUNDS + 1,
+                max: NUM_ROUNDS
+            }
+        );
+    }
+
+    fn check_fixed<const ROUNDS: usize>(input: &[Trit]) {
+        let mut expected = [0u8; 500];
+        let mut troika = Troika::new(ROUNDS).unwrap();
+        troika.absorb(input);
+        troika.squeeze(&mut expected);
+
+        for &backend in [
+            FtroikaBackend::Scalar,
+            FtroikaBackend::Sse2,
+            FtroikaBackend::Avx2,
+        ]
+        .iter()
+        .filter(|backend| backend.is_supported())
+        {
+            let mut ftroika = FixedFtroika::<ROUNDS>::with_backend(backend).unwrap();
+            assert_eq!(ftroika.backend(), backend);
+            let mut output = [0u8; 500];
+            ftroika.absorb(input);
+            ftroika.squeeze(&mut output);
+            assert_eq!(output[..], expected[..], "{:?} {} rounds", backend, ROUNDS);
+        }
+    }
+
+    #[test]
+    fn test_fixed_rounds() {
+        let mut input = [0u8; 600];
+        for (i, trit) in input.iter_mut().enumerate() {
+            *trit = ((i * 3 + i / 7) % 3) as Trit;
+        }
+        check_fixed::<1>(&input);
+        check_fixed::<13>(&input);
+        check_fixed::<NUM_ROUNDS>(&input);
+
+        let mut output = [0u8; 243];
+        let mut ftroika = Ftroika24::default();
+        ftroika.absorb(&[0; 243]);
+        ftroika.squeeze(&mut output);
+        assert_eq!(output[..], HASH[..]);
+        assert_eq!(ftroika.midstate().num_rounds(), NUM_ROUNDS);
+    }
+
+    #[test]
+    fn test_packed() {
+        use crate::encoding::{pack_t5b1, tryte_byte, tryte_trits, unpack_t5b1};
+
+        let mut input = [0u8; 600];
+        for (i, trit) in input.iter_mut().enumerate() {
+            *trit = ((i * i + i / 3) % 3) as Trit;
+        }
+        let mut packed = [0u8; 120];
+        let mut trytes = [0u8; 200];
+        for (tryte, trits) in trytes.iter_mut().zip(input.chunks(3)) {
+            *tryte = tryte_byte(trits);
+        }
+        let trytes = core::str::from_utf8(&trytes).unwrap();
+
+        for &length in [0usize, 1, 4, 5, 242, 243, 244, 600].iter() {
+            let bytes = &mut packed[..length.div_ceil(TRITS_PER_T5B1)];
+            pack_t5b1(&input[..length], bytes);
+            let mut expected = Ftroika::default();
+            expected.absorb_sequence(&input[..7]);
+            expected.absorb_sequence(&input[..length]);
+            let mut ftroika = Ftroika::default();
+            ftroika.absorb_sequence(&input[..7]);
+            ftroika.absorb_packed(bytes, length).unwrap();
+            assert_eq!(ftroika.midstate(), expected.midstate(), "{} trits", length);
+            if length % 3 == 0 {
+                let mut ftroika = Ftroika::default();
+                ftroika.absorb_sequence(&input[..7]);
+                ftroika.absorb_trytes(&trytes[..length / 3]).unwrap();
+                assert_eq!(ftroika.midstate(), expected.midstate(), "{} trits", length);
+            }
+        }
+
+        let mut ftroika = Ftroika::default();
+        ftroika.absorb(&input);
+        let mut expected = [0u8; 600];
+        let mut copy = ftroika;
+        copy.squeeze(&mut expected);
+        let mut output = [0u8; 599];
+        copy = ftroika;
+        copy.squeeze_packed(&mut packed, 599).unwrap();
+        unpack_t5b1(&packed, &mut output).unwrap();
+        assert_eq!(output[..], expected[..599]);
+        let mut trytes = [0u8; 200];
+        copy = ftroika;
+        copy.squeeze_trytes(&mut trytes);
+        for (trits, &tryte) in expected.chunks(3).zip(trytes.iter()) {
+            assert_eq!(tryte_trits(tryte).unwrap(), trits);
+        }
+        // Starting within a slice, so that bytes cross slice boundaries
+        copy = ftroika;
+        copy.squeeze(&mut [0; 2]);
+        copy.squeeze_packed(&mut packed[..119], 595).unwrap();
+        unpack_t5b1(&packed[..119], &mut output[..595]).unwrap();
+        assert_eq!(output[..595], expected[2..597]);
+        copy = ftroika;
+        copy.squeeze(&mut [0; 1]);
+        copy.squeeze_trytes(&mut trytes[..199]);
+        for (trits, &tryte) in expected[1..].chunks(3).zip(trytes[..199].iter()) {
+            assert_eq!(tryte_trits(tryte).unwrap(), trits);
+        }
+
+        // Absorbing after the whole rate was squeezed
+        let mut expected = ftroika;
+        expected.squeeze(&mut [0; 243]);
+        let mut copy = expected;
+        expected.absorb_sequence(&input[..10]);
+        pack_t5b1(&input[..10], &mut packed[..2]);
+        copy.absorb_packed(&packed[..2], 10).unwrap();
+        assert_eq!(copy.midstate(), expected.midstate());
+        // and after a partial squeeze, which overwrites the squeezed trits
+        let mut expected = ftroika;
+        expected.squeeze(&mut [0; 100]);
+        let mut copy = expected;
+        expected.absorb_sequence(&input[..10]);
+        copy.absorb_packed(&packed[..2], 10).unwrap();
+        assert_eq!(copy.midstate(), expected.midstate());
+        pack_t5b1(&input[..240], &mut packed[..48]);
+        let mut fixed = Ftroika24::default();
+        fixed.absorb_packed(&packed[..48], 240).unwrap();
+        let mut ftroika = Ftroika::default();
+        ftroika.absorb_sequence(&input[..240]);
+        assert_eq!(fixed.midstate(), ftroika.midstate());
+
+        let before = ftroika.midstate();
+        assert_eq!(
+            ftroika.absorb_packed(&[0, 0], 11),
+            Err(Error::InvalidLength {
+                expected: 3,
+                actual: 2
+            })
+        );
+        assert_eq!(
+            ftroika.absorb_packed(&[0, 122], 10),
+            Err(Error::InvalidEncoding {
+                encoding: "T5B1",
+                index: 1
+            })
+        );
+        assert!(ftroika.absorb_packed(&[81], 4).is_err());
+        assert_eq!(
+            ftroika.absorb_trytes("ABc"),
+            Err(Error::InvalidEncoding {
+                encoding: "tryte",
+                index: 2
+            })
+        );
+        assert!(ftroika.squeeze_packed(&mut [0; 2], 11).is_err());
+        assert_eq!(ftroika.midstate(), before);
+    }
 }
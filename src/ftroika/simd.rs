@@ -0,0 +1,398 @@
+//! SSE2 and AVX2 versions of the Ftroika permutation.
+//!
+//! The 27 rowcols of the state are stored member-major: the first, second
+//! and third trit of every tryte each get a block of `STRIDE` slots, so
+//! that `sub_trytes` and the parity mixing work on whole vectors.
+
+use super::T27;
+use crate::constants::{
+    COLUMNS, FROUND_CONSTANTS, NUM_ROUNDS, ROWS, SHIFT_LANES, SHIFT_ROWS, SLICES, SLICESIZE,
+};
+use core::arch::x86_64::*;
+
+const MASK: u32 = 0x07ff_ffff;
+/// Slots per tryte member, 9 trytes padded to two AVX2 vectors.
+const STRIDE: usize = 16;
+const SLOTS: usize = 3 * STRIDE;
+const TRYTES: usize = SLICESIZE / 3;
+
+const fn slot(rowcol: usize) -> usize {
+    (rowcol % 3) * STRIDE + rowcol / 3
+}
+
+/// Lane rotation of every slot after shift_rows has moved it in place.
+const ROLL_SLOTS: [u32; SLOTS] = {
+    let mut roll = [0; SLOTS];
+    let mut rowcol = 0;
+    while rowcol < SLICESIZE {
+        roll[slot(rowcol)] = SHIFT_LANES[rowcol] as u32;
+        rowcol += 1;
+    }
+    roll
+};
+
+/// `1 << ROLL_SLOTS[slot]`, for rotating with a multiplication.
+const ROLL_FACTORS: [u32; SLOTS] = {
+    let mut factors = [0; SLOTS];
+    let mut slot = 0;
+    while slot < SLOTS {
+        factors[slot] = 1 << ROLL_SLOTS[slot];
+        slot += 1;
+    }
+    factors
+};
+
+trait Vector: Copy {
+    const LANES: usize;
+
+    unsafe fn load(src: &[u32]) -> Self;
+    unsafe fn store(self, dst: &mut [u32]);
+    unsafe fn splat(x: u32) -> Self;
+    unsafe fn and(self, other: Self) -> Self;
+    unsafe fn or(self, other: Self) -> Self;
+    unsafe fn xor(self, other: Self) -> Self;
+    /// Rotate every lane within 27 bits by the `ROLL_SLOTS` entry of
+    /// its slot, the first lane being at slot `at`.
+    unsafe fn roll(self, at: usize) -> Self;
+}
+
+#[derive(Clone, Copy)]
+struct Sse2(__m128i);
+
+impl Vector for Sse2 {
+    const LANES: usize = 4;
+
+    #[inline(always)]
+    unsafe fn load(src: &[u32]) -> Sse2 {
+        debug_assert!(src.len() >= Self::LANES);
+        Sse2(_mm_loadu_si128(src.as_ptr() as *const __m128i))
+    }
+
+    #[inline(always)]
+    unsafe fn store(self, dst: &mut [u32]) {
+        debug_assert!(dst.len() >= Self::LANES);
+        _mm_storeu_si128(dst.as_mut_ptr() as *mut __m128i, self.0)
+    }
+
+    #[inline(always)]
+    unsafe fn splat(x: u32) -> Sse2 {
+        Sse2(_mm_set1_epi32(x as i32))
+    }
+
+    #[inline(always)]
+    unsafe fn and(self, other: Sse2) -> Sse2 {
+        Sse2(_mm_and_si128(self.0, other.0))
+    }
+
+    #[inline(always)]
+    unsafe fn or(self, other: Sse2) -> Sse2 {
+        Sse2(_mm_or_si128(self.0, other.0))
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, other: Sse2) -> Sse2 {
+        Sse2(_mm_xor_si128(self.0, other.0))
+    }
+
+    #[inline(always)]
+    unsafe fn roll(self, at: usize) -> Sse2 {
+        // SSE2 has no per-lane shifts, but multiplying by 1 << by widens
+        // the even and odd lanes to 64 bits with the rotated out bits
+        // sitting right above the low 27
+        let factors = Sse2::load(&ROLL_FACTORS[at..]).0;
+        let even = _mm_mul_epu32(self.0, factors);
+        let odd = _mm_mul_epu32(_mm_srli_epi64(self.0, 32), _mm_srli_epi64(factors, 32));
+        let even = _mm_or_si128(even, _mm_srli_epi64(even, 27));
+        let odd = _mm_or_si128(odd, _mm_srli_epi64(odd, 27));
+        let even = _mm_and_si128(even, _mm_set_epi32(0, -1, 0, -1));
+        Sse2(_mm_or_si128(even, _mm_slli_epi64(odd, 32))).and(Sse2::splat(MASK))
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Avx2(__m256i);
+
+impl Vector for Avx2 {
+    const LANES: usize = 8;
+
+    #[inline(always)]
+    unsafe fn load(src: &[u32]) -> Avx2 {
+        debug_assert!(src.len() >= Self::LANES);
+        Avx2(_mm256_loadu_si256(src.as_ptr() as *const __m256i))
+    }
+
+    #[inline(always)]
+    unsafe fn store(self, dst: &mut [u32]) {
+        debug_assert!(dst.len() >= Self::LANES);
+        _mm256_storeu_si256(dst.as_mut_ptr() as *mut __m256i, self.0)
+    }
+
+    #[inline(always)]
+    unsafe fn splat(x: u32) -> Avx2 {
+        Avx2(_mm256_set1_epi32(x as i32))
+    }
+
+    #[inline(always)]
+    unsafe fn and(self, other: Avx2) -> Avx2 {
+        Avx2(_mm256_and_si256(self.0, other.0))
+    }
+
+    #[inline(always)]
+    unsafe fn or(self, other: Avx2) -> Avx2 {
+        Avx2(_mm256_or_si256(self.0, other.0))
+    }
+
+    #[inline(always)]
+    unsafe fn xor(self, other: Avx2) -> Avx2 {
+        Avx2(_mm256_xor_si256(self.0, other.0))
+    }
+
+    #[inline(always)]
+    unsafe fn roll(self, at: usize) -> Avx2 {
+        let by = Avx2::load(&ROLL_SLOTS[at..]).0;
+        let left = _mm256_sllv_epi32(self.0, by);
+        let right = _mm256_srlv_epi32(self.0, _mm256_sub_epi32(_mm256_set1_epi32(27), by));
+        Avx2(_mm256_or_si256(left, right)).and(Avx2::splat(MASK))
+    }
+}
+
+/// A vector of T27 values in the p/n representation.
+#[derive(Clone, Copy)]
+struct VT27<V> {
+    p: V,
+    n: V,
+}
+
+impl<V: Vector> VT27<V> {
+    #[inline(always)]
+    unsafe fn load(planes: &Planes, at: usize) -> VT27<V> {
+        VT27 {
+            p: V::load(&planes.p[at..]),
+            n: V::load(&planes.n[at..]),
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn store(self, planes: &mut Planes, at: usize) {
+        self.p.store(&mut planes.p[at..]);
+        self.n.store(&mut planes.n[at..]);
+    }
+
+    #[inline(always)]
+    unsafe fn add(self, other: VT27<V>) -> VT27<V> {
+        // Same as T27::add
+        let t = self.p.or(other.n).xor(self.n.or(other.p));
+        VT27 {
+            p: self.n.or(other.n).xor(t),
+            n: self.p.or(other.p).xor(t),
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn mul(self, other: VT27<V>) -> VT27<V> {
+        VT27 {
+            p: self.p.and(other.p).or(self.n.and(other.n)),
+            n: self.p.and(other.n).or(self.n.and(other.p)),
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn clean(self) -> VT27<V> {
+        let mask = V::splat(MASK);
+        VT27 {
+            p: self.p.and(mask),
+            n: self.n.and(mask),
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn roll(self, at: usize) -> VT27<V> {
+        VT27 {
+            p: self.p.roll(at),
+            n: self.n.roll(at),
+        }
+    }
+}
+
+/// The Ftroika state in member-major layout.
+#[derive(Clone, Copy)]
+struct Planes {
+    p: [u32; SLOTS],
+    n: [u32; SLOTS],
+}
+
+impl Planes {
+    fn load(state: &[T27; SLICESIZE]) -> Planes {
+        let mut planes = Planes {
+            p: [0; SLOTS],
+            n: [0; SLOTS],
+        };
+        for (rowcol, t27) in state.iter().enumerate() {
+            planes.p[slot(rowcol)] = t27.p;
+            planes.n[slot(rowcol)] = t27.n;
+        }
+        planes
+    }
+
+    fn store(&self, state: &mut [T27; SLICESIZE]) {
+        for (rowcol, t27) in state.iter_mut().enumerate() {
+            *t27 = T27::new(self.p[slot(rowcol)], self.n[slot(rowcol)]);
+        }
+    }
+
+    fn get(&self, slot: usize) -> T27 {
+        T27::new(self.p[slot], self.n[slot])
+    }
+
+    fn set(&mut self, slot: usize, t27: T27) {
+        self.p[slot] = t27.p;
+        self.n[slot] = t27.n;
+    }
+}
+
+#[inline(always)]
+unsafe fn sub_trytes<V: Vector>(planes: &mut Planes) {
+    let minus = VT27 {
+        p: V::splat(0),
+        n: V::splat(MASK),
+    };
+    for at in (0..TRYTES).step_by(V::LANES) {
+        let a0 = VT27::<V>::load(planes, at);
+        let a1 = VT27::<V>::load(planes, STRIDE + at);
+        let a2 = VT27::<V>::load(planes, 2 * STRIDE + at);
+        let d = a0.add(minus);
+        let e = d.mul(a1).add(a2);
+        let f = e.mul(a1).add(d);
+        let g = e.mul(f).add(a1);
+        e.clean().store(planes, 2 * STRIDE + at);
+        f.clean().store(planes, STRIDE + at);
+        g.clean().store(planes, at);
+    }
+}
+
+#[inline(always)]
+unsafe fn shift_rows_lanes<V: Vector>(planes: &mut Planes) {
+    let mut shifted = *planes;
+    for (rowcol, &dst) in SHIFT_ROWS.iter().enumerate() {
+        shifted.set(slot(dst as usize), planes.get(slot(rowcol)));
+    }
+    for member in 0..3 {
+        for at in (member * STRIDE..member * STRIDE + TRYTES).step_by(V::LANES) {
+            VT27::<V>::load(&shifted, at).roll(at).store(planes, at);
+        }
+    }
+}
+
+#[inline(always)]
+unsafe fn add_column_parity<V: Vector>(planes: &mut Planes) {
+    // Row `row` of member `member` sits at slots 3 * row.. in its block,
+    // so summing three shifted loads leaves the column parities in the
+    // first three lanes
+    let mut sums = Planes {
+        p: [0; SLOTS],
+        n: [0; SLOTS],
+    };
+    for member in 0..3 {
+        let at = member * STRIDE;
+        VT27::<V>::load(planes, at)
+            .add(VT27::load(planes, at + 3))
+            .add(VT27::load(planes, at + 6))
+            .store(&mut sums, at);
+    }
+    let parity = |col: usize| sums.get(slot(col));
+
+    let mut mix = Planes {
+        p: [0; SLOTS],
+        n: [0; SLOTS],
+    };
+    for col in 0..COLUMNS {
+        let t1 = parity((col + COLUMNS - 1) % COLUMNS);
+        let t2 = parity((col + 1) % COLUMNS).roll(SLICES - 1);
+        let sum_to_add = t1.add(&t2);
+        for row in 0..ROWS {
+            mix.set(slot(COLUMNS * row + col), sum_to_add);
+        }
+    }
+
+    for member in 0..3 {
+        for at in (member * STRIDE..member * STRIDE + TRYTES).step_by(V::LANES) {
+            VT27::<V>::load(planes, at)
+                .add(VT27::load(&mix, at))
+                .store(planes, at);
+        }
+    }
+}
+
+/// Round constants in the p/n form, for every round.
+type RoundConstants = [[[u32; 2]; COLUMNS]; NUM_ROUNDS];
+
+fn add_round_constant(planes: &mut Planes, constants: &[[u32; 2]; COLUMNS]) {
+    for (col, &[p, n]) in constants.iter().enumerate() {
+        let t27 = planes.get(slot(col)).add(&T27::new(p, n));
+        planes.set(slot(col), t27);
+    }
+}
+
+#[inline(always)]
+unsafe fn permutation<V: Vector>(
+    state: &mut [T27; SLICESIZE],
+    num_rounds: usize,
+    constants: &RoundConstants,
+) {
+    let mut planes = Planes::load(state);
+    for round_constants in constants[..num_rounds].iter() {
+        sub_trytes::<V>(&mut planes);
+        shift_rows_lanes::<V>(&mut planes);
+        add_column_parity::<V>(&mut planes);
+        add_round_constant(&mut planes, round_constants);
+    }
+    planes.store(state);
+}
+
+/// `ROUNDS` rounds of standard Troika. Unlike the scalar code, the
+/// vector rounds are not unrolled: 24 copies of them measured slower
+/// than the loop, whose count and constants are still known here.
+#[inline(always)]
+unsafe fn fixed_permutation<V: Vector, const ROUNDS: usize>(state: &mut [T27; SLICESIZE]) {
+    permutation::<V>(state, ROUNDS, &FROUND_CONSTANTS)
+}
+
+/// # Safety
+///
+/// The CPU must support SSE2.
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn permutation_sse2(
+    state: &mut [T27; SLICESIZE],
+    num_rounds: usize,
+    constants: &RoundConstants,
+) {
+    permutation::<Sse2>(state, num_rounds, constants)
+}
+
+/// # Safety
+///
+/// The CPU must support AVX2.
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn permutation_avx2(
+    state: &mut [T27; SLICESIZE],
+    num_rounds: usize,
+    constants: &RoundConstants,
+) {
+    permutation::<Avx2>(state, num_rounds, constants)
+}
+
+/// # Safety
+///
+/// The CPU must support SSE2.
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn fixed_permutation_sse2<const ROUNDS: usize>(state: &mut [T27; SLICESIZE]) {
+    fixed_permutation::<Sse2, ROUNDS>(state)
+}
+
+/// # Safety
+///
+/// The CPU must support AVX2.
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn fixed_permutation_avx2<const ROUNDS: usize>(state: &mut [T27; SLICESIZE]) {
+    fixed_permutation::<Avx2, ROUNDS>(state)
+}
@@ -0,0 +1,163 @@
+use super::constants::{Trit, TROIKA_RATE};
+use crate::encoding::{char_to_tryte, tryte_to_char};
+use crate::{Error, TritSlice};
+use core::cmp::Ordering;
+use core::{fmt, hash, str};
+
+const HASH_TRYTES: usize = TROIKA_RATE / 3;
+
+/// The Hash struct is the 243-trit output of a Troika sponge.
+///
+/// It is displayed and parsed as 81 trytes in the IOTA tryte alphabet.
+/// Equality is checked in constant time.
+#[derive(Clone, Copy)]
+pub struct Hash([Trit; TROIKA_RATE]);
+
+impl Hash {
+    pub fn from_trits(trits: &[Trit]) -> crate::Result<Hash> {
+        if trits.len() != TROIKA_RATE {
+            return Err(Error::InvalidLength {
+                expected: TROIKA_RATE,
+                actual: trits.len(),
+            });
+        }
+        let mut hash = [0; TROIKA_RATE];
+        hash.copy_from_slice(TritSlice::new(trits)?.as_trits());
+        Ok(Hash(hash))
+    }
+
+    pub fn as_trits(&self) -> &[Trit] {
+        &self.0
+    }
+
+    pub fn to_trits(self) -> [Trit; TROIKA_RATE] {
+        self.0
+    }
+}
+
+impl From<[Trit; TROIKA_RATE]> for Hash {
+    fn from(trits: [Trit; TROIKA_RATE]) -> Hash {
+        Hash(trits)
+    }
+}
+
+impl AsRef<[Trit]> for Hash {
+    fn as_ref(&self) -> &[Trit] {
+        &self.0
+    }
+}
+
+impl PartialEq for Hash {
+    fn eq(&self, other: &Hash) -> bool {
+        // Accumulate every difference so the running time does not
+        // depend on the position of the first mismatching trit
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+    }
+}
+
+impl Eq for Hash {}
+
+impl PartialOrd for Hash {
+    fn partial_cmp(&self, other: &Hash) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hash {
+    fn cmp(&self, other: &Hash) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl hash::Hash for Hash {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl fmt::Display for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for tryte in self.0.chunks(3) {
+            write!(f, "{}", tryte_to_char(tryte))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Hash({})", self)
+    }
+}
+
+impl str::FromStr for Hash {
+    type Err = Error;
+
+    fn from_str(trytes: &str) -> crate::Result<Hash> {
+        if trytes.len() != HASH_TRYTES {
+            return Err(Error::InvalidLength {
+                expected: HASH_TRYTES,
+                actual: trytes.len(),
+            });
+        }
+        let mut hash = [0; TROIKA_RATE];
+        for (index, (trits, tryte)) in hash.chunks_mut(3).zip(trytes.bytes()).enumerate() {
+            char_to_tryte(tryte, trits).ok_or(Error::InvalidEncoding {
+                encoding: "tryte",
+                index,
+            })?;
+        }
+        Ok(Hash(hash))
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test_hash {
+    use super::*;
+    use alloc::string::{String, ToString};
+
+    const TRYTES: &str =
+        "OGUMPMUGTPMCBIQAKMHFYMVZNZNPFGVK9NFMIXQ9LSMXCSORHBUDLFJZUKAHGGUNYETPVFLSZQURKH9XN";
+
+    #[test]
+    fn test_display_from_str() {
+        let hash = crate::hash(&[0u8; 243]);
+        assert_eq!(hash.to_string(), TRYTES);
+        assert_eq!(TRYTES.parse::<Hash>().unwrap(), hash);
+    }
+
+    #[test]
+    fn test_all_trytes() {
+        let trytes: String = crate::encoding::TRYTE_ALPHABET
+            .iter()
+            .cycle()
+            .take(HASH_TRYTES)
+            .map(|&c| c as char)
+            .collect();
+        assert_eq!(trytes.parse::<Hash>().unwrap().to_string(), trytes);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!("9".parse::<Hash>().is_err());
+        assert!(TRYTES.replace('N', "n").parse::<Hash>().is_err());
+        assert!(Hash::from_trits(&[0; 81]).is_err());
+        assert!(Hash::from_trits(&[3; 243]).is_err());
+    }
+
+    #[test]
+    fn test_eq_ord() {
+        let zero = Hash::from([0; 243]);
+        let mut trits = [0; 243];
+        trits[242] = 1;
+        let one = Hash::from(trits);
+
+        assert_eq!(zero, Hash::from_trits(&[0; 243]).unwrap());
+        assert_ne!(zero, one);
+        assert!(zero < one);
+    }
+}
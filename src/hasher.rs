@@ -0,0 +1,185 @@
+use super::constants::{Trit, TROIKA_RATE};
+use crate::ftroika::Ftroika;
+use crate::{Hash, Result, Sponge, TritSlice};
+
+/// A Sponge that can absorb a message in pieces before it is padded.
+pub trait StreamingSponge: Sponge {
+    /// Absorb trits without padding. Consecutive calls behave like a
+    /// single call on the concatenated input.
+    fn absorb_sequence(&mut self, trits: &[Trit]);
+    /// Pad the message absorbed so far. The sponge is ready to be
+    /// squeezed afterwards.
+    fn absorb_padding(&mut self);
+
+    /// Absorb a whole message like `Sponge::absorb`, but reject it
+    /// before touching the state if it contains an invalid trit.
+    fn try_absorb(&mut self, trits: &[Trit]) -> Result<()> {
+        self.absorb_trits(TritSlice::new(trits)?);
+        Ok(())
+    }
+
+    /// Absorb a whole message that has already been validated.
+    fn absorb_trits(&mut self, trits: TritSlice) {
+        self.absorb(trits.as_trits());
+    }
+}
+
+/// The TroikaHasher struct hashes a message that is fed in chunks
+/// of any size. The result does not depend on how the message is split.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TroikaHasher<S = Ftroika> {
+    sponge: S,
+}
+
+impl<S: StreamingSponge> TroikaHasher<S> {
+    pub fn new() -> TroikaHasher<S> {
+        TroikaHasher::default()
+    }
+
+    /// Create a hasher on top of an already configured sponge,
+    /// e.g. one with a reduced number of rounds.
+    pub fn with_sponge(sponge: S) -> TroikaHasher<S> {
+        TroikaHasher { sponge }
+    }
+
+    pub fn update(&mut self, trits: &[Trit]) {
+        self.sponge.absorb_sequence(trits);
+    }
+
+    /// Like `update`, but the chunk is rejected if it contains an invalid trit.
+    pub fn try_update(&mut self, trits: &[Trit]) -> Result<()> {
+        self.update(TritSlice::new(trits)?.as_trits());
+        Ok(())
+    }
+
+    pub fn finalize(self) -> Hash {
+        let mut hash = [0; TROIKA_RATE];
+        self.finalize_into(&mut hash);
+        Hash::from(hash)
+    }
+
+    pub fn finalize_into(self, hash: &mut [Trit]) {
+        self.finalize_xof().read(hash);
+    }
+
+    /// Finish absorbing and return a reader that squeezes an output
+    /// stream of arbitrary length.
+    pub fn finalize_xof(mut self) -> TroikaReader<S> {
+        self.sponge.absorb_padding();
+        TroikaReader {
+            sponge: self.sponge,
+        }
+    }
+}
+
+/// The TroikaReader struct is the extendable output of a TroikaHasher.
+/// Successive reads return the same stream as a single large squeeze.
+#[derive(Clone, Copy, Debug)]
+pub struct TroikaReader<S = Ftroika> {
+    sponge: S,
+}
+
+impl<S: StreamingSponge> TroikaReader<S> {
+    pub fn read(&mut self, trits: &mut [Trit]) {
+        self.sponge.squeeze(trits);
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod test_hasher {
+    use super::*;
+    use crate::fixtures::message;
+    use crate::troika::Troika;
+    use alloc::{vec, vec::Vec};
+
+    fn hash_in_chunks<S: StreamingSponge>(message: &[Trit], chunk_size: usize) -> Hash {
+        let mut hasher = TroikaHasher::<S>::new();
+        for chunk in message.chunks(chunk_size) {
+            hasher.update(chunk);
+        }
+        hasher.finalize()
+    }
+
+    #[test]
+    fn test_chunked_matches_sponge() {
+        for &length in [0, 1, 242, 243, 244, 486].iter() {
+            let message: [Trit; 486] = message(length);
+            let mut expected = [0u8; 243];
+            let mut ftroika = Ftroika::default();
+            ftroika.absorb(&message[..length]);
+            ftroika.squeeze(&mut expected);
+
+            for &chunk_size in [1, 2, 26, 81, 242, 243, 1000].iter() {
+                assert_eq!(
+                    hash_in_chunks::<Ftroika>(&message[..length], chunk_size),
+                    Hash::from(expected)
+                );
+                assert_eq!(
+                    hash_in_chunks::<Troika>(&message[..length], chunk_size),
+                    Hash::from(expected)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_updates() {
+        let message: [Trit; 300] = message(300);
+        let mut hasher = TroikaHasher::<Ftroika>::new();
+        hasher.update(&[]);
+        hasher.update(&message[..100]);
+        hasher.update(&[]);
+        hasher.update(&message[100..]);
+
+        assert_eq!(hasher.finalize(), hash_in_chunks::<Ftroika>(&message, 300));
+    }
+
+    fn squeeze_xof<S: StreamingSponge>(message: &[Trit], length: usize) -> Vec<Trit> {
+        let mut hasher = TroikaHasher::<S>::new();
+        hasher.update(message);
+        let mut output = vec![0; length];
+        hasher.finalize_xof().read(&mut output);
+        output
+    }
+
+    #[test]
+    fn test_xof_implementations_agree() {
+        let message: [Trit; 500] = message(500);
+        for length in (0..1500).step_by(7).chain(vec![243, 486, 729, 730]) {
+            assert_eq!(
+                squeeze_xof::<Troika>(&message, length),
+                squeeze_xof::<Ftroika>(&message, length),
+                "output length {}",
+                length
+            );
+        }
+    }
+
+    #[test]
+    fn test_xof_successive_reads() {
+        let message: [Trit; 100] = message(100);
+        let expected = squeeze_xof::<Ftroika>(&message, 2000);
+        assert_eq!(expected[..243], crate::hash(&message).as_trits()[..]);
+
+        for &read_size in [1, 5, 81, 242, 243, 244, 700].iter() {
+            let mut hasher = TroikaHasher::<Troika>::new();
+            hasher.update(&message);
+            let mut reader = hasher.finalize_xof();
+            let mut freader = TroikaHasher::<Ftroika>::new();
+            freader.update(&message);
+            let mut freader = freader.finalize_xof();
+
+            let mut output = vec![0; 2000];
+            let mut foutput = vec![0; 2000];
+            for (chunk, fchunk) in output
+                .chunks_mut(read_size)
+                .zip(foutput.chunks_mut(read_size))
+            {
+                reader.read(chunk);
+                freader.read(fchunk);
+            }
+            assert_eq!(output, expected);
+            assert_eq!(foutput, expected);
+        }
+    }
+}
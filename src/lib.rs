@@ -1,14 +1,199 @@
+//! Troika hash in Rust.
+//!
+//! The crate is `no_std`. The `alloc` feature adds the APIs that return
+//! vectors or strings, and the `std` feature (on by default) adds
+//! runtime CPU feature detection and `std::error::Error` for [`Error`].
+
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+/// Run `$body` with `$round` bound to each of `0..$rounds`, unrolled into
+/// straight-line code. `$rounds` is at most `NUM_ROUNDS`; when it is a
+/// constant the rounds past it compile away.
+macro_rules! unroll_rounds {
+    ($rounds:expr, |$round:ident| $body:block) => {
+        unroll_rounds!(@ $rounds, $round, $body,
+            [0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23])
+    };
+    (@ $rounds:expr, $round:ident, $body:block, [$($idx:literal)*]) => {{
+        $(
+            if $idx < $rounds {
+                let $round: usize = $idx;
+                $body
+            }
+        )*
+    }};
+}
+
+mod backend;
+#[cfg(feature = "alloc")]
+mod batch;
 mod constants;
+pub mod encoding;
+mod error;
+#[cfg(test)]
+mod fixtures;
 pub mod ftroika;
+mod hash;
+mod hasher;
+mod midstate;
+mod params;
+mod permutation;
+#[cfg(feature = "std")]
+pub mod pow;
+#[cfg(feature = "research")]
+pub mod research;
+mod trits;
 pub mod troika;
 
+pub use backend::{AnyTroika, Backend};
+#[cfg(feature = "alloc")]
+pub use batch::{hash_many, BATCH_SIZE};
+#[cfg(feature = "rayon")]
+pub use batch::{par_hash_batch, ParBatchHasher};
+pub use constants::{Trit, NUM_ROUNDS, STATE_SIZE, TROIKA_RATE};
+pub use error::Error;
+pub use hash::Hash;
+pub use hasher::{StreamingSponge, TroikaHasher, TroikaReader};
+pub use midstate::Midstate;
+pub use params::{DefaultParams, Rounds, TroikaParams, TroikaWith};
+pub use permutation::Permutation;
+pub use trits::TritSlice;
+
+pub use ftroika::FtroikaBackend;
+
+// Ftroika is preferred when both features are enabled. Use `AnyTroika`
+// to pick an implementation at runtime.
 #[cfg(feature = "ftroika")]
 pub use ftroika::Ftroika as Troika;
 
-#[cfg(feature = "origin")]
+#[cfg(all(feature = "origin", not(feature = "ftroika")))]
 pub use troika::Troika;
 
 pub use sponge_preview::Sponge;
 
 use core::result;
-pub type Result<T> = result::Result<T, failure::Error>;
+pub type Result<T> = result::Result<T, Error>;
+
+/// Hash a message with the default Troika sponge.
+pub fn hash(message: &[Trit]) -> Hash {
+    let mut hasher = TroikaHasher::<ftroika::Ftroika>::new();
+    hasher.update(message);
+    hasher.finalize()
+}
+
+/// The first trit of the capacity in `compress`. The sponge starts
+/// with a zero capacity.
+const COMPRESS_DOMAIN: Trit = 1;
+
+/// Hash two hashes into one with a single permutation, e.g. for the
+/// nodes of a Merkle tree. `left` and `right` fill the first two thirds
+/// of the state, after which `COMPRESS_DOMAIN` separates it from the
+/// sponge. Both inputs are fed forward into the rate of the permuted
+/// state, which is the result.
+///
+/// This is not the same as `hash` of the two hashes concatenated.
+pub fn compress(left: &Hash, right: &Hash) -> Hash {
+    let mut state = [0; STATE_SIZE];
+    state[..TROIKA_RATE].copy_from_slice(left.as_trits());
+    state[TROIKA_RATE..2 * TROIKA_RATE].copy_from_slice(right.as_trits());
+    state[2 * TROIKA_RATE] = COMPRESS_DOMAIN;
+    ftroika::Ftroika::permute_unchecked(&mut state, NUM_ROUNDS);
+    let mut output = [0; TROIKA_RATE];
+    for (i, trit) in output.iter_mut().enumerate() {
+        *trit = (state[i] + left.as_trits()[i] + right.as_trits()[i]) % 3;
+    }
+    Hash::from(output)
+}
+
+#[cfg(test)]
+mod test_lib {
+    use super::*;
+
+    const TRYTES: &str =
+        "OGUMPMUGTPMCBIQAKMHFYMVZNZNPFGVK9NFMIXQ9LSMXCSORHBUDLFJZUKAHGGUNYETPVFLSZQURKH9XN";
+
+    // Runs without alloc too, so `cargo test --no-default-features`
+    // checks the no_std build against the same hash
+    #[test]
+    fn test_hash() {
+        let expected: Hash = TRYTES.parse().unwrap();
+        assert_eq!(hash(&[0; 243]), expected);
+
+        let mut output = [0; 243];
+        let mut troika = troika::Troika::default();
+        troika.absorb(&[0; 243]);
+        troika.squeeze(&mut output);
+        assert_eq!(Hash::from(output), expected);
+    }
+
+    const ZERO: &str =
+        "999999999999999999999999999999999999999999999999999999999999999999999999999999999";
+    const ONES: &str =
+        "F9QWLTWFPURSJOEWNZZNULSULHSEONQBWEGGIJMAXZLGRTVYHPXZUD9VDFGGWV9Y9QV99KTWZTMODXKXS";
+
+    /// Left, right and expected output of `compress`.
+    const COMPRESS_KAT: [(&str, &str, &str); 5] = [
+        (
+            ZERO,
+            ZERO,
+            "KFQSEHGQUZBQWCOLDDYEMRUZRRFRUMWUND9I9JORUGYZOLTDFSECYQPEOIWCCBPRLVFTLKCLRQLHKUFPP",
+        ),
+        (
+            TRYTES,
+            ZERO,
+            "KIGTHZXBUJQAIMURSPLMTFZDDRHLNWHZNLZZGUYWUBSAFLVDCHNFFCSRPCLIGMEXFETUEYDCLOEQCTYKQ",
+        ),
+        (
+            ZERO,
+            TRYTES,
+            "ECZEVXGHVHSZSKWNNNUUHYGDZTTXJIVDAADSGZCOFTZGUNMMMEZNMAP9SOYKHJTNBWZSQLVMHKIYJQAQS",
+        ),
+        (
+            TRYTES,
+            ONES,
+            "MERNPFSAWQQRYULEDUAXUGWGSJCQBJBJKMKCXAUGGJOPADVFOHUXAYEUGRCAPBWJQCPXWGP9LYGKEZLIG",
+        ),
+        (
+            ONES,
+            TRYTES,
+            "KJLOQWRHYVHZWKCKVLFIFQPQR9VBJQLOWTCDULSYUXLQAMNXCC9VAFDICKEDSLJAZAFNAHTZCZXHVNHAC",
+        ),
+    ];
+
+    #[test]
+    fn test_compress() {
+        assert_eq!(ONES.parse::<Hash>().unwrap(), hash(&[1; 243]));
+        for &(left, right, expected) in COMPRESS_KAT.iter() {
+            let (left, right): (Hash, Hash) = (left.parse().unwrap(), right.parse().unwrap());
+            let output = compress(&left, &right);
+            assert_eq!(output, expected.parse().unwrap());
+
+            // The same steps on the reference permutation
+            let mut state = [0; STATE_SIZE];
+            state[..TROIKA_RATE].copy_from_slice(left.as_trits());
+            state[TROIKA_RATE..2 * TROIKA_RATE].copy_from_slice(right.as_trits());
+            state[2 * TROIKA_RATE] = COMPRESS_DOMAIN;
+            troika::Troika::permute(&mut state).unwrap();
+            for (i, &trit) in output.as_trits().iter().enumerate() {
+                assert_eq!(
+                    trit,
+                    (state[i] + left.as_trits()[i] + right.as_trits()[i]) % 3
+                );
+            }
+        }
+        let mut message = [0; 2 * TROIKA_RATE];
+        message[TROIKA_RATE..].copy_from_slice(TRYTES.parse::<Hash>().unwrap().as_trits());
+        assert_ne!(hash(&message), COMPRESS_KAT[2].2.parse().unwrap());
+    }
+
+    #[test]
+    fn test_detect_backend() {
+        assert!(FtroikaBackend::detect().is_supported());
+        assert!(FtroikaBackend::Scalar.is_supported());
+    }
+}
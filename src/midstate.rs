@@ -0,0 +1,292 @@
+use super::constants::{Trit, STATE_SIZE, TROIKA_RATE};
+use crate::encoding::{pack_t5b1, unpack_t5b1};
+use crate::permutation::check_rounds;
+use crate::{Error, Result};
+use core::fmt;
+
+const HEADER_SIZE: usize = 3;
+const STATE_BYTES: usize = STATE_SIZE.div_ceil(5);
+
+/// The Midstate struct is a snapshot of a Troika sponge: its round
+/// count, its position within the rate and its 729 trits. It can be
+/// taken from and restored on any backend, in any phase of absorbing or
+/// squeezing.
+///
+/// The byte format is the version, the round count, the position and
+/// the state packed five trits per byte (T5B1).
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Midstate {
+    num_rounds: usize,
+    idx: usize,
+    state: [Trit; STATE_SIZE],
+}
+
+impl Midstate {
+    /// The version written by `to_bytes`.
+    pub const VERSION: u8 = 1;
+    /// The length of the byte format.
+    pub const SIZE: usize = HEADER_SIZE + STATE_BYTES;
+
+    pub(crate) fn new(num_rounds: usize, idx: usize, state: [Trit; STATE_SIZE]) -> Midstate {
+        debug_assert!(idx <= TROIKA_RATE);
+        Midstate {
+            num_rounds,
+            idx,
+            state,
+        }
+    }
+
+    pub fn num_rounds(&self) -> usize {
+        self.num_rounds
+    }
+
+    /// The number of trits absorbed into or squeezed from the current
+    /// block. A fully squeezed block is only permuted on the next read.
+    pub fn idx(&self) -> usize {
+        self.idx
+    }
+
+    pub fn state(&self) -> &[Trit; STATE_SIZE] {
+        &self.state
+    }
+
+    pub fn to_bytes(&self) -> [u8; Midstate::SIZE] {
+        let mut bytes = [0; Midstate::SIZE];
+        bytes[0] = Midstate::VERSION;
+        bytes[1] = self.num_rounds as u8;
+        bytes[2] = self.idx as u8;
+        pack_t5b1(&self.state, &mut bytes[HEADER_SIZE..]);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Midstate> {
+        if bytes.len() != Midstate::SIZE {
+            return Err(Error::InvalidLength {
+                expected: Midstate::SIZE,
+                actual: bytes.len(),
+            });
+        }
+        if bytes[0] != Midstate::VERSION {
+            return Err(Error::InvalidEncoding {
+                encoding: "midstate version",
+                index: 0,
+            });
+        }
+        let num_rounds = usize::from(bytes[1]);
+        check_rounds(num_rounds)?;
+        let idx = usize::from(bytes[2]);
+        if idx > TROIKA_RATE {
+            return Err(Error::InvalidEncoding {
+                encoding: "midstate",
+                index: 2,
+            });
+        }
+        let mut state = [0; STATE_SIZE];
+        unpack_t5b1(&bytes[HEADER_SIZE..], &mut state).map_err(|err| match err {
+            Error::InvalidEncoding { encoding, index } => Error::InvalidEncoding {
+                encoding,
+                index: HEADER_SIZE + index,
+            },
+            err => err,
+        })?;
+        Ok(Midstate::new(num_rounds, idx, state))
+    }
+}
+
+impl fmt::Debug for Midstate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Midstate: [rounds: [{}], idx: [{}], state: {:?}",
+            self.num_rounds,
+            self.idx,
+            &self.state[..],
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::Midstate;
+    use core::fmt;
+    use serde::de::{self, SeqAccess, Visitor};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    impl Serialize for Midstate {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(&self.to_bytes())
+        }
+    }
+
+    struct MidstateVisitor;
+
+    impl<'de> Visitor<'de> for MidstateVisitor {
+        type Value = Midstate;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{} bytes of a Troika midstate", Midstate::SIZE)
+        }
+
+        fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Midstate, E> {
+            Midstate::from_bytes(bytes).map_err(E::custom)
+        }
+
+        // Formats without a byte type, such as JSON, use a sequence
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Midstate, A::Error> {
+            let mut bytes = [0; Midstate::SIZE];
+            let mut length = 0;
+            while let Some(byte) = seq.next_element()? {
+                if length == Midstate::SIZE {
+                    return Err(de::Error::invalid_length(length + 1, &self));
+                }
+                bytes[length] = byte;
+                length += 1;
+            }
+            self.visit_bytes(&bytes[..length])
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Midstate {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Midstate, D::Error> {
+            deserializer.deserialize_bytes(MidstateVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_midstate {
+    use super::*;
+    use crate::fixtures::message;
+    use crate::ftroika::Ftroika;
+    use crate::troika::Troika;
+    use crate::{AnyTroika, Backend, Sponge, StreamingSponge};
+
+    #[test]
+    fn test_restore_absorbing() {
+        let message: [Trit; 1000] = message(1000);
+        let mut expected = [0; 300];
+        let mut troika = Troika::default();
+        troika.absorb(&message);
+        troika.squeeze(&mut expected);
+
+        for &split in [0, 1, 242, 243, 500, 1000].iter() {
+            let mut prefix = Troika::default();
+            prefix.absorb_sequence(&message[..split]);
+            let midstate = prefix.midstate();
+            let mut fprefix = Ftroika::default();
+            fprefix.absorb_sequence(&message[..split]);
+            assert_eq!(fprefix.midstate(), midstate);
+
+            let midstate = Midstate::from_bytes(&midstate.to_bytes()).unwrap();
+            for &backend in Backend::ALL.iter().filter(|b| b.is_supported()) {
+                let mut sponge = AnyTroika::new(backend).unwrap();
+                sponge.restore(&midstate);
+                sponge.absorb_sequence(&message[split..]);
+                sponge.absorb_padding();
+                let mut output = [0; 300];
+                sponge.squeeze(&mut output);
+                assert_eq!(output[..], expected[..], "{:?} split {}", backend, split);
+            }
+        }
+    }
+
+    #[test]
+    fn test_restore_squeezing() {
+        let message: [Trit; 100] = message(100);
+        let mut expected = [0; 800];
+        let mut ftroika = Ftroika::default();
+        ftroika.absorb(&message[..100]);
+        ftroika.squeeze(&mut expected);
+
+        for &split in [0, 1, 243, 300, 729].iter() {
+            let mut troika = Troika::default();
+            troika.absorb(&message[..100]);
+            troika.squeeze(&mut [0; 800][..split]);
+            let midstate = troika.midstate();
+
+            let mut ftroika = Ftroika::default();
+            ftroika.restore(&midstate);
+            assert_eq!(ftroika.midstate(), midstate);
+            let mut output = [0; 800];
+            let mut foutput = [0; 800];
+            troika.squeeze(&mut output[split..]);
+            ftroika.squeeze(&mut foutput[split..]);
+            assert_eq!(output[split..], expected[split..]);
+            assert_eq!(foutput[split..], expected[split..]);
+        }
+    }
+
+    #[test]
+    fn test_restore_squeezing_then_absorbing() {
+        let message: [Trit; 300] = message(300);
+        for &split in [1, 100, 243, 300].iter() {
+            let mut troika = Troika::default();
+            troika.absorb(&message[..100]);
+            troika.squeeze(&mut [0; 300][..split]);
+            let midstate = Midstate::from_bytes(&troika.midstate().to_bytes()).unwrap();
+            troika.absorb_sequence(&message[..50]);
+            let expected = troika.midstate();
+            let mut expected_output = [0; 243];
+            troika.absorb(&message);
+            troika.squeeze(&mut expected_output);
+
+            for &backend in Backend::ALL.iter().filter(|b| b.is_supported()) {
+                let mut sponge = AnyTroika::new(backend).unwrap();
+                sponge.restore(&midstate);
+                sponge.absorb_sequence(&message[..50]);
+                assert_eq!(sponge.midstate(), expected, "{:?} split {}", backend, split);
+                let mut output = [0; 243];
+                sponge.absorb(&message);
+                sponge.squeeze(&mut output);
+                assert_eq!(
+                    output[..],
+                    expected_output[..],
+                    "{:?} split {}",
+                    backend,
+                    split
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_bytes() {
+        let mut troika = Troika::new(5).unwrap();
+        troika.absorb_sequence(&message::<300>(300));
+        let bytes = troika.midstate().to_bytes();
+        assert_eq!(Midstate::from_bytes(&bytes).unwrap().num_rounds(), 5);
+        assert_eq!(Midstate::from_bytes(&bytes).unwrap().idx(), 57);
+
+        assert!(Midstate::from_bytes(&bytes[1..]).is_err());
+        let mut corrupt = bytes;
+        corrupt[0] = 2;
+        assert!(Midstate::from_bytes(&corrupt).is_err());
+        let mut corrupt = bytes;
+        corrupt[1] = 0;
+        assert!(Midstate::from_bytes(&corrupt).is_err());
+        let mut corrupt = bytes;
+        corrupt[2] = 244;
+        assert!(Midstate::from_bytes(&corrupt).is_err());
+        let mut corrupt = bytes;
+        corrupt[Midstate::SIZE - 1] = 81;
+        assert_eq!(
+            Midstate::from_bytes(&corrupt),
+            Err(Error::InvalidEncoding {
+                encoding: "T5B1",
+                index: Midstate::SIZE - 1
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut ftroika = Ftroika::default();
+        ftroika.absorb_sequence(&message::<500>(500));
+        let midstate = ftroika.midstate();
+
+        let json = serde_json::to_string(&midstate).unwrap();
+        assert_eq!(serde_json::from_str::<Midstate>(&json).unwrap(), midstate);
+        assert!(serde_json::from_str::<Midstate>("[1, 24, 0]").is_err());
+    }
+}
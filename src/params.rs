@@ -0,0 +1,317 @@
+use super::constants::{
+    invert_sbox, pack_round_constants, Trit, COLUMNS, NUM_ROUNDS, ROUND_CONSTANTS, SBOX_LOOKUP,
+    SLICES, TRYTE_TRITS,
+};
+use crate::backend::{AnyTroika, Backend};
+use crate::hasher::StreamingSponge;
+use crate::{Result, Sponge};
+use core::fmt;
+use core::marker::PhantomData;
+
+/// The TroikaParams trait describes a member of the Troika family: its
+/// round count, its round constants and its S-box. Every item defaults
+/// to standard Troika, so an implementation only overrides what it
+/// changes.
+///
+/// The parameters are checked when a sponge using them is compiled:
+/// `NUM_ROUNDS` must be in `1..=NUM_ROUNDS`, the constants must be trits
+/// and the S-box must be a permutation of `0..27`.
+///
+/// ```
+/// use troika::{Sponge, Trit, TroikaParams, TroikaWith};
+///
+/// struct Reduced;
+///
+/// impl TroikaParams for Reduced {
+///     const NUM_ROUNDS: usize = 12;
+/// }
+///
+/// let mut troika = TroikaWith::<Reduced>::default();
+/// let mut output = [0 as Trit; 243];
+/// troika.absorb(&[0; 243]);
+/// troika.squeeze(&mut output);
+/// ```
+pub trait TroikaParams: 'static {
+    const NUM_ROUNDS: usize = NUM_ROUNDS;
+    /// The constants added to the first row of every slice, 27 per
+    /// slice, for each round. Only the first `NUM_ROUNDS` are used.
+    const ROUND_CONSTANTS: [[Trit; COLUMNS * SLICES]; NUM_ROUNDS] = ROUND_CONSTANTS;
+    /// The S-box, indexed by `9 * a0 + 3 * a1 + a2` for a tryte `a0 a1 a2`.
+    const SBOX: [Trit; 27] = SBOX_LOOKUP;
+}
+
+/// The parameters of standard Troika.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DefaultParams;
+
+impl TroikaParams for DefaultParams {}
+
+/// The parameters of standard Troika reduced to `N` rounds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Rounds<const N: usize>;
+
+impl<const N: usize> TroikaParams for Rounds<N> {
+    const NUM_ROUNDS: usize = N;
+}
+
+/// The tables both engines read their round constants and S-box from.
+#[derive(Debug)]
+pub(crate) struct Tables {
+    pub(crate) round_constants: [[Trit; COLUMNS * SLICES]; NUM_ROUNDS],
+    pub(crate) fround_constants: [[[u32; 2]; COLUMNS]; NUM_ROUNDS],
+    pub(crate) sbox: [Trit; 27],
+    pub(crate) inv_sbox: [Trit; 27],
+    /// The trits of every output of `sbox`, most significant first.
+    pub(crate) sbox_trits: [[Trit; 3]; 27],
+    /// Whether `sbox` is the one Ftroika evaluates without a lookup.
+    pub(crate) default_sbox: bool,
+}
+
+impl Tables {
+    /// Fails to compile unless `P` is valid.
+    pub(crate) fn of<P: TroikaParams>() -> &'static Tables {
+        let () = TablesOf::<P>::CHECK;
+        TablesOf::<P>::TABLES
+    }
+}
+
+struct TablesOf<P>(PhantomData<P>);
+
+impl<P: TroikaParams> TablesOf<P> {
+    const CHECK: () = check_params(P::NUM_ROUNDS, &P::ROUND_CONSTANTS);
+
+    const TABLES: &'static Tables = &Tables {
+        round_constants: P::ROUND_CONSTANTS,
+        fround_constants: pack_round_constants(&P::ROUND_CONSTANTS),
+        sbox: P::SBOX,
+        inv_sbox: invert_sbox(&P::SBOX),
+        sbox_trits: sbox_trits(&P::SBOX),
+        default_sbox: same_sbox(&P::SBOX, &SBOX_LOOKUP),
+    };
+}
+
+const fn check_params(num_rounds: usize, round_constants: &[[Trit; COLUMNS * SLICES]; NUM_ROUNDS]) {
+    assert!(
+        num_rounds >= 1 && num_rounds <= NUM_ROUNDS,
+        "TroikaParams::NUM_ROUNDS out of range"
+    );
+    let mut round = 0;
+    while round < NUM_ROUNDS {
+        let mut idx = 0;
+        while idx < COLUMNS * SLICES {
+            assert!(
+                round_constants[round][idx] < 3,
+                "TroikaParams::ROUND_CONSTANTS contains an invalid trit"
+            );
+            idx += 1;
+        }
+        round += 1;
+    }
+}
+
+const fn sbox_trits(sbox: &[Trit; 27]) -> [[Trit; 3]; 27] {
+    let mut trits = [[0; 3]; 27];
+    let mut input = 0;
+    while input < 27 {
+        trits[input] = TRYTE_TRITS[sbox[input] as usize];
+        input += 1;
+    }
+    trits
+}
+
+const fn same_sbox(a: &[Trit; 27], b: &[Trit; 27]) -> bool {
+    let mut idx = 0;
+    while idx < 27 {
+        if a[idx] != b[idx] {
+            return false;
+        }
+        idx += 1;
+    }
+    true
+}
+
+/// The TroikaWith struct is a Sponge for the Troika variant described by
+/// `P`, running on any backend. With `DefaultParams` it gives the same
+/// results as `Troika`.
+pub struct TroikaWith<P> {
+    sponge: AnyTroika,
+    // P only names the parameters, so it does not affect Send or Sync
+    params: PhantomData<fn() -> P>,
+}
+
+impl<P: TroikaParams> TroikaWith<P> {
+    /// Create a sponge on the given backend.
+    /// Fails if the running CPU does not support it.
+    pub fn new(backend: Backend) -> Result<TroikaWith<P>> {
+        let mut sponge = AnyTroika::new(backend)?;
+        sponge.set_params(P::NUM_ROUNDS, Tables::of::<P>());
+        Ok(TroikaWith {
+            sponge,
+            params: PhantomData,
+        })
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.sponge.backend()
+    }
+}
+
+impl<P: TroikaParams> Default for TroikaWith<P> {
+    fn default() -> TroikaWith<P> {
+        // The detected backend is always supported
+        TroikaWith::new(Backend::detect()).unwrap()
+    }
+}
+
+impl<P> Clone for TroikaWith<P> {
+    fn clone(&self) -> TroikaWith<P> {
+        *self
+    }
+}
+
+impl<P> Copy for TroikaWith<P> {}
+
+impl<P> fmt::Debug for TroikaWith<P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TroikaWith: {:?}", self.sponge)
+    }
+}
+
+impl<P: TroikaParams> Sponge for TroikaWith<P> {
+    fn absorb(&mut self, trits: &[Trit]) {
+        self.sponge.absorb(trits);
+    }
+
+    fn squeeze(&mut self, trits: &mut [Trit]) {
+        self.sponge.squeeze(trits);
+    }
+
+    fn reset(&mut self) {
+        self.sponge.reset();
+    }
+}
+
+impl<P: TroikaParams> StreamingSponge for TroikaWith<P> {
+    fn absorb_sequence(&mut self, trits: &[Trit]) {
+        self.sponge.absorb_sequence(trits);
+    }
+
+    fn absorb_padding(&mut self) {
+        self.sponge.absorb_padding();
+    }
+}
+
+#[cfg(test)]
+mod test_params {
+    use super::*;
+    use crate::fixtures::message;
+    use crate::ftroika::Ftroika;
+    use crate::troika::Troika;
+    use crate::{Permutation, STATE_SIZE, TROIKA_RATE};
+
+    struct Reduced;
+
+    impl TroikaParams for Reduced {
+        const NUM_ROUNDS: usize = 5;
+    }
+
+    /// Standard Troika with every S-box output incremented, which only
+    /// the lookup path of Ftroika can compute.
+    struct Rotated;
+
+    impl TroikaParams for Rotated {
+        const SBOX: [Trit; 27] = {
+            let mut sbox = SBOX_LOOKUP;
+            let mut idx = 0;
+            while idx < 27 {
+                sbox[idx] = (SBOX_LOOKUP[idx] + 1) % 27;
+                idx += 1;
+            }
+            sbox
+        };
+    }
+
+    /// Standard Troika with every round constant negated.
+    struct Negated;
+
+    impl TroikaParams for Negated {
+        const ROUND_CONSTANTS: [[Trit; COLUMNS * SLICES]; NUM_ROUNDS] = {
+            let mut constants = ROUND_CONSTANTS;
+            let mut round = 0;
+            while round < NUM_ROUNDS {
+                let mut idx = 0;
+                while idx < COLUMNS * SLICES {
+                    constants[round][idx] = (3 - constants[round][idx]) % 3;
+                    idx += 1;
+                }
+                round += 1;
+            }
+            constants
+        };
+    }
+
+    fn squeeze<P: TroikaParams>(backend: Backend, message: &[Trit]) -> [Trit; 300] {
+        let mut output = [0; 300];
+        let mut troika = TroikaWith::<P>::new(backend).unwrap();
+        troika.absorb(message);
+        troika.squeeze(&mut output);
+        output
+    }
+
+    fn check_backends_agree<P: TroikaParams>() -> [Trit; 300] {
+        let message: [Trit; 600] = message(600);
+        let expected = squeeze::<P>(Backend::Reference, &message);
+        for &backend in Backend::ALL.iter().filter(|b| b.is_supported()) {
+            assert_eq!(
+                squeeze::<P>(backend, &message)[..],
+                expected[..],
+                "{:?}",
+                backend
+            );
+        }
+        expected
+    }
+
+    #[test]
+    fn test_default_params() {
+        let message: [Trit; 600] = message(600);
+        let mut expected = [0; 300];
+        let mut troika = Troika::default();
+        troika.absorb(&message);
+        troika.squeeze(&mut expected);
+        assert_eq!(check_backends_agree::<DefaultParams>()[..], expected[..]);
+    }
+
+    #[test]
+    fn test_reduced_rounds() {
+        let message: [Trit; 600] = message(600);
+        let mut expected = [0; 300];
+        let mut ftroika = Ftroika::new(5).unwrap();
+        ftroika.absorb(&message);
+        ftroika.squeeze(&mut expected);
+        assert_eq!(check_backends_agree::<Reduced>()[..], expected[..]);
+    }
+
+    #[test]
+    fn test_custom_tables() {
+        let default = check_backends_agree::<DefaultParams>();
+        let rotated = check_backends_agree::<Rotated>();
+        let negated = check_backends_agree::<Negated>();
+        assert_ne!(rotated[..], default[..]);
+        assert_ne!(negated[..], default[..]);
+        assert_ne!(negated[..], rotated[..]);
+    }
+
+    #[test]
+    fn test_tables() {
+        assert!(Tables::of::<DefaultParams>().default_sbox);
+        assert!(!Tables::of::<Rotated>().default_sbox);
+        let mut state = [0; STATE_SIZE];
+        Troika::permute(&mut state).unwrap();
+        let mut troika = TroikaWith::<DefaultParams>::new(Backend::Reference).unwrap();
+        troika.absorb_sequence(&[0; TROIKA_RATE]);
+        let mut output = [0; TROIKA_RATE];
+        troika.squeeze(&mut output);
+        assert_eq!(output[..], state[..TROIKA_RATE]);
+    }
+}
@@ -0,0 +1,137 @@
+use super::constants::{Trit, NUM_ROUNDS, STATE_SIZE};
+use crate::{Error, Result, TritSlice};
+
+/// A backend of the Troika permutation that works on a caller-supplied
+/// state of `STATE_SIZE` trits.
+///
+/// The state is laid out slice by slice, each slice row by row, i.e. the
+/// trit at `slice`, `row` and `col` is at index `27 * slice + 9 * row + col`.
+/// The first `TROIKA_RATE` trits are the rate of the sponge.
+pub trait Permutation {
+    /// Apply the first `num_rounds` rounds of the permutation to `state`.
+    /// The state is left untouched if it contains an invalid trit or
+    /// `num_rounds` is not in `1..=NUM_ROUNDS`.
+    fn permute_rounds(state: &mut [Trit; STATE_SIZE], num_rounds: usize) -> Result<()> {
+        check_rounds(num_rounds)?;
+        TritSlice::new(state)?;
+        Self::permute_unchecked(state, num_rounds);
+        Ok(())
+    }
+
+    /// Apply the full permutation to `state`.
+    fn permute(state: &mut [Trit; STATE_SIZE]) -> Result<()> {
+        Self::permute_rounds(state, NUM_ROUNDS)
+    }
+
+    /// Undo the first `num_rounds` rounds of the permutation, so that
+    /// calling it after `permute_rounds` with the same round count gives
+    /// back the original state. Invalid input is rejected like in
+    /// `permute_rounds`.
+    fn inverse_permutation(state: &mut [Trit; STATE_SIZE], num_rounds: usize) -> Result<()> {
+        check_rounds(num_rounds)?;
+        TritSlice::new(state)?;
+        Self::inverse_unchecked(state, num_rounds);
+        Ok(())
+    }
+
+    /// Apply `num_rounds` rounds to a state that is known to be valid.
+    #[doc(hidden)]
+    fn permute_unchecked(state: &mut [Trit; STATE_SIZE], num_rounds: usize);
+
+    /// Undo `num_rounds` rounds of a state that is known to be valid.
+    #[doc(hidden)]
+    fn inverse_unchecked(state: &mut [Trit; STATE_SIZE], num_rounds: usize);
+}
+
+pub(crate) fn check_rounds(num_rounds: usize) -> Result<()> {
+    if num_rounds == 0 || num_rounds > NUM_ROUNDS {
+        return Err(Error::InvalidRounds {
+            rounds: num_rounds,
+            max: NUM_ROUNDS,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_permutation {
+    use super::*;
+    use crate::fixtures::state;
+    use crate::ftroika::Ftroika;
+    use crate::troika::Troika;
+    use crate::{Hash, TROIKA_RATE};
+    use rand::{thread_rng, Rng};
+
+    #[test]
+    fn test_backends_agree() {
+        for num_rounds in 1..=NUM_ROUNDS {
+            let mut state = state(num_rounds);
+            let mut fstate = state;
+            Troika::permute_rounds(&mut state, num_rounds).unwrap();
+            Ftroika::permute_rounds(&mut fstate, num_rounds).unwrap();
+            assert_eq!(state[..], fstate[..], "{} rounds", num_rounds);
+        }
+    }
+
+    #[test]
+    fn test_matches_sponge() {
+        // Hashing the empty message permutes a state holding only the padding
+        let mut state = [0; STATE_SIZE];
+        state[0] = 1;
+        Ftroika::permute(&mut state).unwrap();
+        assert_eq!(
+            Hash::from_trits(&state[..TROIKA_RATE]).unwrap(),
+            crate::hash(&[])
+        );
+    }
+
+    #[test]
+    fn test_inverse_round_trips() {
+        let mut rng = thread_rng();
+        for _ in 0..50 {
+            let mut original = [0; STATE_SIZE];
+            for trit in original.iter_mut() {
+                *trit = rng.gen_range(0, 3);
+            }
+            let num_rounds = rng.gen_range(1, NUM_ROUNDS + 1);
+
+            let mut state = original;
+            Troika::permute_rounds(&mut state, num_rounds).unwrap();
+            let permuted = state;
+            Ftroika::inverse_permutation(&mut state, num_rounds).unwrap();
+            assert_eq!(state[..], original[..], "{} rounds", num_rounds);
+            Troika::inverse_permutation(&mut state, num_rounds).unwrap();
+            Ftroika::permute_rounds(&mut state, num_rounds).unwrap();
+            assert_eq!(state[..], original[..], "{} rounds", num_rounds);
+
+            let mut fstate = permuted;
+            Troika::inverse_permutation(&mut fstate, num_rounds).unwrap();
+            assert_eq!(fstate[..], original[..], "{} rounds", num_rounds);
+        }
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let mut state = state(0);
+        let original = state;
+        assert_eq!(
+            Troika::permute_rounds(&mut state, NUM_ROUNDS + 1),
+            Err(Error::InvalidRounds {
+                rounds: NUM_ROUNDS + 1,
+                max: NUM_ROUNDS
+            })
+        );
+        state[100] = 3;
+        assert_eq!(
+            Ftroika::permute(&mut state),
+            Err(Error::InvalidTrit {
+                index: 100,
+                value: 3
+            })
+        );
+        assert!(Troika::inverse_permutation(&mut state, 1).is_err());
+        assert!(Ftroika::inverse_permutation(&mut state, 0).is_err());
+        state[100] = original[100];
+        assert_eq!(state[..], original[..]);
+    }
+}
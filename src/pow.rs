@@ -0,0 +1,248 @@
+//! Proof of work for IOTA transactions.
+//!
+//! A transaction is `TRANSACTION_TRITS` trits ending in a nonce of
+//! `NONCE_TRITS` trits. Its proof of work is valid for a minimum weight
+//! magnitude `mwm` when its Troika hash ends in at least `mwm` zero
+//! trits.
+
+use crate::constants::{Trit, TROIKA_RATE};
+use crate::ftroika::Ftroika24;
+use crate::hasher::StreamingSponge;
+use crate::{Error, Hash, Result, Sponge, TritSlice};
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::vec::Vec;
+
+/// The length of a transaction.
+pub const TRANSACTION_TRITS: usize = 8019;
+/// The length of the nonce at the end of a transaction.
+pub const NONCE_TRITS: usize = 81;
+/// The start of the nonce in a transaction.
+pub const NONCE_OFFSET: usize = TRANSACTION_TRITS - NONCE_TRITS;
+
+// The nonce is in the last block, so every nonce continues from the
+// sponge that absorbed all blocks before it
+const LAST_BLOCK: usize = TRANSACTION_TRITS - TROIKA_RATE;
+
+/// Nonce trits fixed to the thread index, so threads never try the same
+/// nonce.
+const THREAD_TRITS: usize = 8;
+
+/// The NonceSearch struct holds a transaction with all but its last
+/// block absorbed, to hash it with many nonces.
+#[derive(Clone, Copy, Debug)]
+pub struct NonceSearch {
+    sponge: Ftroika24,
+    last_block: [Trit; TROIKA_RATE],
+}
+
+impl NonceSearch {
+    /// Absorb all of `transaction` before its last block. Its nonce is
+    /// ignored.
+    pub fn new(transaction: &[Trit]) -> Result<NonceSearch> {
+        check_transaction(transaction)?;
+        let mut sponge = Ftroika24::default();
+        sponge.absorb_sequence(&transaction[..LAST_BLOCK]);
+        let mut last_block = [0; TROIKA_RATE];
+        last_block.copy_from_slice(&transaction[LAST_BLOCK..]);
+        Ok(NonceSearch { sponge, last_block })
+    }
+
+    /// The hash of the transaction with `nonce`.
+    pub fn hash(&self, nonce: &[Trit; NONCE_TRITS]) -> Hash {
+        let mut block = self.last_block;
+        block[NONCE_OFFSET - LAST_BLOCK..].copy_from_slice(nonce);
+        let mut sponge = self.sponge;
+        // Two permutations, for the block and the padding. The hash is
+        // the rate they leave.
+        sponge.absorb(&block);
+        Hash::from(sponge.rate())
+    }
+
+    /// Search for a nonce giving at least `mwm` zero trits on `threads`
+    /// threads, or as many as the CPU runs in parallel if it is 0.
+    /// Returns `None` once `cancel` is set.
+    pub fn search(
+        &self,
+        mwm: usize,
+        threads: usize,
+        cancel: &AtomicBool,
+    ) -> Result<Option<[Trit; NONCE_TRITS]>> {
+        check_mwm(mwm)?;
+        let threads = match threads {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        }
+        .min(3usize.pow(THREAD_TRITS as u32));
+        let found = AtomicBool::new(false);
+        let stop = || cancel.load(Ordering::Relaxed) || found.load(Ordering::Relaxed);
+        let nonces = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|index| {
+                    let (stop, found) = (&stop, &found);
+                    scope.spawn(move || {
+                        let nonce = self.search_from(first_nonce(index), mwm, stop);
+                        if nonce.is_some() {
+                            found.store(true, Ordering::Relaxed);
+                        }
+                        nonce
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("nonce search thread panicked"))
+                .collect::<Vec<_>>()
+        });
+        Ok(nonces.into_iter().flatten().next())
+    }
+
+    fn search_from(
+        &self,
+        mut nonce: [Trit; NONCE_TRITS],
+        mwm: usize,
+        stop: impl Fn() -> bool,
+    ) -> Option<[Trit; NONCE_TRITS]> {
+        while !stop() {
+            if has_weight(&self.hash(&nonce), mwm) {
+                return Some(nonce);
+            }
+            increment(&mut nonce[THREAD_TRITS..]);
+        }
+        None
+    }
+}
+
+/// Check that the hash of `transaction` ends in at least `mwm` zero
+/// trits. Returns false for a malformed transaction or an `mwm` above
+/// `TROIKA_RATE`.
+pub fn verify_pow(transaction: &[Trit], mwm: usize) -> bool {
+    check_transaction(transaction).is_ok()
+        && check_mwm(mwm).is_ok()
+        && has_weight(&crate::hash(transaction), mwm)
+}
+
+fn check_transaction(transaction: &[Trit]) -> Result<()> {
+    if transaction.len() != TRANSACTION_TRITS {
+        return Err(Error::InvalidLength {
+            expected: TRANSACTION_TRITS,
+            actual: transaction.len(),
+        });
+    }
+    TritSlice::new(transaction)?;
+    Ok(())
+}
+
+fn check_mwm(mwm: usize) -> Result<()> {
+    if mwm > TROIKA_RATE {
+        return Err(Error::InvalidWeight {
+            mwm,
+            max: TROIKA_RATE,
+        });
+    }
+    Ok(())
+}
+
+fn has_weight(hash: &Hash, mwm: usize) -> bool {
+    hash.as_trits()[TROIKA_RATE - mwm..]
+        .iter()
+        .all(|&trit| trit == 0)
+}
+
+/// The first nonce of thread `index`, with its index in the first trits.
+fn first_nonce(index: usize) -> [Trit; NONCE_TRITS] {
+    let mut nonce = [0; NONCE_TRITS];
+    let mut index = index;
+    for trit in nonce[..THREAD_TRITS].iter_mut() {
+        *trit = (index % 3) as Trit;
+        index /= 3;
+    }
+    nonce
+}
+
+/// Add one to little-endian trits, wrapping around.
+fn increment(trits: &mut [Trit]) {
+    for trit in trits.iter_mut() {
+        *trit = (*trit + 1) % 3;
+        if *trit != 0 {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_pow {
+    use super::*;
+    use crate::fixtures::message;
+
+    #[test]
+    fn test_hash() {
+        let mut transaction: [Trit; TRANSACTION_TRITS] = message(TRANSACTION_TRITS);
+        let search = NonceSearch::new(&transaction).unwrap();
+        let mut nonce = [0; NONCE_TRITS];
+        nonce[3] = 2;
+        transaction[NONCE_OFFSET..].copy_from_slice(&nonce);
+        assert_eq!(search.hash(&nonce), crate::hash(&transaction));
+    }
+
+    #[test]
+    fn test_search() {
+        let cancel = AtomicBool::new(false);
+        let mut transaction: [Trit; TRANSACTION_TRITS] = message(TRANSACTION_TRITS);
+        let search = NonceSearch::new(&transaction).unwrap();
+        for &(mwm, threads) in [(0, 1), (5, 1), (5, 3), (6, 0)].iter() {
+            let nonce = search.search(mwm, threads, &cancel).unwrap().unwrap();
+            transaction[NONCE_OFFSET..].copy_from_slice(&nonce);
+            assert!(verify_pow(&transaction, mwm), "mwm {}", mwm);
+        }
+        assert!(!verify_pow(&transaction, 20));
+
+        let nonce = search.search(5, 1, &cancel).unwrap().unwrap();
+        transaction[NONCE_OFFSET..].copy_from_slice(&nonce);
+        transaction[NONCE_OFFSET - 1] = (transaction[NONCE_OFFSET - 1] + 1) % 3;
+        assert!(!verify_pow(&transaction, 5));
+    }
+
+    #[test]
+    fn test_cancel() {
+        let transaction: [Trit; TRANSACTION_TRITS] = message(TRANSACTION_TRITS);
+        let search = NonceSearch::new(&transaction).unwrap();
+        let cancel = AtomicBool::new(true);
+        assert_eq!(search.search(TROIKA_RATE, 2, &cancel), Ok(None));
+    }
+
+    #[test]
+    fn test_invalid() {
+        let transaction: [Trit; TRANSACTION_TRITS] = message(TRANSACTION_TRITS);
+        let search = NonceSearch::new(&transaction).unwrap();
+        let cancel = AtomicBool::new(false);
+        assert_eq!(
+            search.search(TROIKA_RATE + 1, 1, &cancel),
+            Err(Error::InvalidWeight {
+                mwm: TROIKA_RATE + 1,
+                max: TROIKA_RATE
+            })
+        );
+        assert_eq!(
+            NonceSearch::new(&transaction[1..]).unwrap_err(),
+            Error::InvalidLength {
+                expected: TRANSACTION_TRITS,
+                actual: TRANSACTION_TRITS - 1
+            }
+        );
+        let mut invalid = transaction;
+        invalid[7] = 3;
+        assert!(NonceSearch::new(&invalid).is_err());
+        assert!(!verify_pow(&invalid, 0));
+        assert!(!verify_pow(&transaction[1..], 0));
+        assert!(!verify_pow(&transaction, TROIKA_RATE + 1));
+    }
+
+    #[test]
+    fn test_increment() {
+        let mut trits = [2, 2, 1];
+        increment(&mut trits);
+        assert_eq!(trits, [0, 0, 2]);
+        assert_eq!(first_nonce(5)[..THREAD_TRITS], [2, 1, 0, 0, 0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,222 @@
+//! The step functions of the Troika round, for studying reduced-round
+//! variants. Only available with the `research` feature.
+//!
+//! A round applies `sub_trytes`, `shift_rows_lanes`, `add_column_parity`
+//! and `add_round_constant` in that order.
+
+use super::constants::{Trit, STATE_SIZE};
+use crate::permutation::check_rounds;
+use crate::{Result, TritSlice};
+
+/// The RoundSteps trait gives access to the steps of a Troika round
+/// on a full 729-trit state.
+pub trait RoundSteps: Sized {
+    /// Create an instance holding `state`, which must only contain valid trits.
+    fn from_state(state: &[Trit; STATE_SIZE]) -> Self;
+    /// The current state in the flat layout, `27 * slice + rowcol`.
+    fn to_state(&self) -> [Trit; STATE_SIZE];
+
+    /// Apply the S-box to every tryte.
+    fn sub_trytes(&mut self);
+    /// Move every rowcol to its new position within the slice.
+    fn shift_rows(&mut self);
+    /// Rotate every rowcol across the slices.
+    fn shift_lanes(&mut self);
+    /// `shift_rows` followed by `shift_lanes`.
+    fn shift_rows_lanes(&mut self) {
+        self.shift_rows();
+        self.shift_lanes();
+    }
+    /// Add the parities of the neighbouring columns to every trit.
+    fn add_column_parity(&mut self);
+    /// Add the constants of `round`, which must be below `NUM_ROUNDS`.
+    fn add_round_constant(&mut self, round: usize);
+
+    /// Apply the inverse S-box to every tryte.
+    fn inverse_sub_trytes(&mut self);
+    /// Undo `shift_rows_lanes`.
+    fn inverse_shift_rows_lanes(&mut self);
+    /// Undo `add_column_parity`.
+    fn inverse_add_column_parity(&mut self);
+    /// Subtract the constants of `round`.
+    fn inverse_add_round_constant(&mut self, round: usize);
+
+    /// Apply `step` of `round`.
+    fn apply(&mut self, step: Step, round: usize) {
+        match step {
+            Step::SubTrytes => self.sub_trytes(),
+            Step::ShiftRowsLanes => self.shift_rows_lanes(),
+            Step::AddColumnParity => self.add_column_parity(),
+            Step::AddRoundConstant => self.add_round_constant(round),
+        }
+    }
+
+    /// Undo `step` of `round`.
+    fn apply_inverse(&mut self, step: Step, round: usize) {
+        match step {
+            Step::SubTrytes => self.inverse_sub_trytes(),
+            Step::ShiftRowsLanes => self.inverse_shift_rows_lanes(),
+            Step::AddColumnParity => self.inverse_add_column_parity(),
+            Step::AddRoundConstant => self.inverse_add_round_constant(round),
+        }
+    }
+}
+
+/// The Step enum names the steps of a round.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Step {
+    SubTrytes,
+    ShiftRowsLanes,
+    AddColumnParity,
+    AddRoundConstant,
+}
+
+impl Step {
+    /// The steps in the order a round applies them.
+    pub const ALL: [Step; 4] = [
+        Step::SubTrytes,
+        Step::ShiftRowsLanes,
+        Step::AddColumnParity,
+        Step::AddRoundConstant,
+    ];
+}
+
+/// The TraceStep struct is the state right after `step` of `round`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TraceStep {
+    pub round: usize,
+    pub step: Step,
+    pub state: [Trit; STATE_SIZE],
+}
+
+/// The RoundTrace struct iterates over the state after every step of
+/// every round of the permutation.
+#[derive(Clone, Copy, Debug)]
+pub struct RoundTrace<S> {
+    steps: S,
+    num_rounds: usize,
+    round: usize,
+    step: usize,
+}
+
+impl<S: RoundSteps> RoundTrace<S> {
+    /// Trace `num_rounds` rounds of the permutation of `state`.
+    pub fn new(state: &[Trit; STATE_SIZE], num_rounds: usize) -> Result<RoundTrace<S>> {
+        check_rounds(num_rounds)?;
+        TritSlice::new(state)?;
+        Ok(RoundTrace {
+            steps: S::from_state(state),
+            num_rounds,
+            round: 0,
+            step: 0,
+        })
+    }
+}
+
+impl<S: RoundSteps> Iterator for RoundTrace<S> {
+    type Item = TraceStep;
+
+    fn next(&mut self) -> Option<TraceStep> {
+        if self.round == self.num_rounds {
+            return None;
+        }
+        let step = Step::ALL[self.step];
+        self.steps.apply(step, self.round);
+        let trace = TraceStep {
+            round: self.round,
+            step,
+            state: self.steps.to_state(),
+        };
+        self.step += 1;
+        if self.step == Step::ALL.len() {
+            self.step = 0;
+            self.round += 1;
+        }
+        Some(trace)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = (self.num_rounds - self.round) * Step::ALL.len() - self.step;
+        (left, Some(left))
+    }
+}
+
+impl<S: RoundSteps> ExactSizeIterator for RoundTrace<S> {}
+
+#[cfg(test)]
+mod test_research {
+    use super::*;
+    use crate::fixtures::state;
+    use crate::ftroika::Ftroika;
+    use crate::troika::Troika;
+    use crate::{Error, Permutation, NUM_ROUNDS};
+
+    #[test]
+    fn test_trace_matches_permutation() {
+        for &num_rounds in [1, 3, NUM_ROUNDS].iter() {
+            let mut expected = state(0);
+            Troika::permute_rounds(&mut expected, num_rounds).unwrap();
+
+            let trace = RoundTrace::<Troika>::new(&state(0), num_rounds).unwrap();
+            let ftrace = RoundTrace::<Ftroika>::new(&state(0), num_rounds).unwrap();
+            assert_eq!(trace.len(), 4 * num_rounds);
+            let mut last = None;
+            for (step, fstep) in trace.zip(ftrace) {
+                assert_eq!(step, fstep);
+                last = Some(step);
+            }
+            let last = last.unwrap();
+            assert_eq!(last.round, num_rounds - 1);
+            assert_eq!(last.step, Step::AddRoundConstant);
+            assert_eq!(last.state[..], expected[..]);
+        }
+    }
+
+    #[test]
+    fn test_split_shifts() {
+        let mut troika = Troika::from_state(&state(0));
+        let mut split = Troika::from_state(&state(0));
+        let mut ftroika = Ftroika::from_state(&state(0));
+        troika.shift_rows_lanes();
+        split.shift_rows();
+        split.shift_lanes();
+        assert_eq!(troika.to_state()[..], split.to_state()[..]);
+
+        let mut troika = Troika::from_state(&state(0));
+        troika.shift_rows();
+        ftroika.shift_rows();
+        assert_eq!(troika.to_state()[..], ftroika.to_state()[..]);
+        troika.shift_lanes();
+        ftroika.shift_lanes();
+        assert_eq!(troika.to_state()[..], ftroika.to_state()[..]);
+    }
+
+    fn check_inverse_steps<S: RoundSteps>() {
+        for round in 0..NUM_ROUNDS {
+            for &step in Step::ALL.iter() {
+                let mut steps = S::from_state(&state(0));
+                steps.apply(step, round);
+                assert_ne!(steps.to_state()[..], state(0)[..]);
+                steps.apply_inverse(step, round);
+                assert_eq!(steps.to_state()[..], state(0)[..], "{:?}", step);
+            }
+        }
+    }
+
+    #[test]
+    fn test_inverse_steps() {
+        check_inverse_steps::<Troika>();
+        check_inverse_steps::<Ftroika>();
+    }
+
+    #[test]
+    fn test_invalid_trace() {
+        assert!(RoundTrace::<Troika>::new(&state(0), 0).is_err());
+        let mut state = state(0);
+        state[5] = 3;
+        assert_eq!(
+            RoundTrace::<Ftroika>::new(&state, 1).unwrap_err(),
+            Error::InvalidTrit { index: 5, value: 3 }
+        );
+    }
+}
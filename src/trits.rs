@@ -0,0 +1,79 @@
+use super::constants::Trit;
+use crate::Error;
+
+/// The TritSlice struct is a slice of trits that is known to only
+/// contain the values 0, 1 and 2.
+///
+/// Untrusted input should be validated through it before being absorbed,
+/// since the sponges index lookup tables with the raw trit values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TritSlice<'a>(&'a [Trit]);
+
+impl<'a> TritSlice<'a> {
+    pub fn new(trits: &'a [Trit]) -> Result<TritSlice<'a>, Error> {
+        match trits.iter().position(|&trit| trit > 2) {
+            Some(index) => Err(Error::InvalidTrit {
+                index,
+                value: trits[index],
+            }),
+            None => Ok(TritSlice(trits)),
+        }
+    }
+
+    pub fn as_trits(&self) -> &'a [Trit] {
+        self.0
+    }
+}
+
+impl AsRef<[Trit]> for TritSlice<'_> {
+    fn as_ref(&self) -> &[Trit] {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod test_trits {
+    use super::*;
+    use crate::ftroika::Ftroika;
+    use crate::troika::Troika;
+    use crate::{Sponge, StreamingSponge, TroikaHasher};
+
+    #[test]
+    fn test_validation() {
+        assert!(TritSlice::new(&[]).is_ok());
+        assert_eq!(TritSlice::new(&[0, 1, 2]).unwrap().as_trits(), &[0, 1, 2]);
+        assert_eq!(
+            TritSlice::new(&[0, 1, 2, 3, 255]),
+            Err(Error::InvalidTrit { index: 3, value: 3 })
+        );
+    }
+
+    #[test]
+    fn test_try_absorb() {
+        let mut input = [1u8; 300];
+        input[250] = 7;
+        let expected = Err(Error::InvalidTrit {
+            index: 250,
+            value: 7,
+        });
+
+        let mut troika = Troika::default();
+        let mut ftroika = Ftroika::default();
+        assert_eq!(troika.try_absorb(&input), expected);
+        assert_eq!(ftroika.try_absorb(&input), expected);
+
+        // A rejected input leaves the sponge untouched
+        let mut output = [0u8; 243];
+        let mut foutput = [0u8; 243];
+        troika.try_absorb(&input[..250]).unwrap();
+        troika.squeeze(&mut output);
+        ftroika.try_absorb(&input[..250]).unwrap();
+        ftroika.squeeze(&mut foutput);
+        assert_eq!(output[..], foutput[..]);
+
+        let mut hasher = TroikaHasher::<Ftroika>::new();
+        assert_eq!(hasher.try_update(&input), expected);
+        hasher.try_update(&input[..250]).unwrap();
+        assert_eq!(hasher.finalize(), crate::hash(&input[..250]));
+    }
+}
@@ -1,8 +1,14 @@
 use super::constants::{
-    Trit, COLUMNS, NUM_ROUNDS, NUM_SBOXES, PADDING, ROUND_CONSTANTS, ROWS, SBOX_LOOKUP,
-    SHIFT_ROWS_LANES, SLICES, SLICESIZE, STATE_SIZE, TROIKA_RATE,
+    Trit, COLUMNS, NUM_ROUNDS, NUM_SBOXES, PADDING, PARITY_NEIGHBOURS, ROUND_CONSTANTS, ROWS,
+    SHIFT_ROWS_LANES, SHIFT_ROWS_LANES_DEST, SLICES, SLICESIZE, STATE_SIZE, TROIKA_RATE,
 };
-use crate::{Result, Sponge};
+#[cfg(feature = "research")]
+use super::constants::{SHIFT_LANES, SHIFT_ROWS};
+use crate::ftroika::Ftroika;
+use crate::hasher::StreamingSponge;
+use crate::params::{DefaultParams, Rounds, Tables};
+use crate::permutation::{check_rounds, Permutation};
+use crate::{Midstate, Result, Sponge};
 use core::fmt;
 
 /// The Troika struct is a Sponge that uses the Troika
@@ -10,6 +16,8 @@ use core::fmt;
 #[derive(Clone, Copy)]
 pub struct Troika {
     num_rounds: usize,
+    tables: &'static Tables,
+    idx: usize,
     state: [Trit; STATE_SIZE],
 }
 
@@ -17,6 +25,8 @@ impl Default for Troika {
     fn default() -> Troika {
         Troika {
             num_rounds: NUM_ROUNDS,
+            tables: Tables::of::<DefaultParams>(),
+            idx: 0,
             state: [0u8; STATE_SIZE],
         }
     }
@@ -35,16 +45,47 @@ impl fmt::Debug for Troika {
 
 impl Sponge for Troika {
     fn absorb(&mut self, message: &[Trit]) {
+        self.absorb_with(message, Troika::permutation);
+    }
+
+    fn squeeze(&mut self, hash: &mut [Trit]) {
+        self.squeeze_with(hash, Troika::permutation);
+    }
+
+    fn reset(&mut self) {
+        self.state = [0; STATE_SIZE];
+        self.idx = 0;
+    }
+}
+
+impl StreamingSponge for Troika {
+    fn absorb_sequence(&mut self, trits: &[Trit]) {
+        self.absorb_sequence_with(trits, Troika::permutation);
+    }
+
+    fn absorb_padding(&mut self) {
+        self.absorb_padding_with(Troika::permutation);
+    }
+}
+
+// The sponge logic, shared with FixedTroika, which brings its own permutation
+impl Troika {
+    fn absorb_with(&mut self, message: &[Trit], permutation: impl Fn(&mut Troika)) {
+        self.permute_squeezed(&permutation);
+        // Continue a partly used block, like Ftroika
+        if self.idx != 0 {
+            self.absorb_sequence_with(message, &permutation);
+            self.absorb_padding_with(&permutation);
+            return;
+        }
         let mut message_length = message.len();
         let mut message_idx = 0;
-        let mut trit_idx = 0;
 
         while message_length >= TROIKA_RATE {
             // Copy message block over the state
-            for trit_idx in 0..TROIKA_RATE {
-                self.state[trit_idx] = message[message_idx + trit_idx];
-            }
-            self.permutation();
+            self.state[..TROIKA_RATE]
+                .copy_from_slice(&message[message_idx..message_idx + TROIKA_RATE]);
+            permutation(self);
             message_length -= TROIKA_RATE;
             message_idx += TROIKA_RATE;
         }
@@ -53,77 +94,318 @@ impl Sponge for Troika {
         let mut last_block = [0u8; TROIKA_RATE];
 
         // Copy over last incomplete message block
-        for _ in 0..message_length {
-            last_block[trit_idx] = message[trit_idx];
-            trit_idx += 1;
-        }
+        last_block[..message_length].copy_from_slice(&message[message_idx..]);
 
-        // TODO: Check trit_idx is right here
-        // Apply padding
-        last_block[trit_idx] = PADDING;
+        // Apply padding right after the last message trit
+        last_block[message_length] = PADDING;
 
         // Insert last message block
-        for trit_idx in 0..TROIKA_RATE {
-            self.state[trit_idx] = last_block[trit_idx];
-        }
+        self.state[..TROIKA_RATE].copy_from_slice(&last_block);
+        permutation(self);
+        self.idx = 0;
     }
 
-    fn squeeze(&mut self, hash: &mut [Trit]) {
+    fn squeeze_with(&mut self, hash: &mut [Trit], permutation: impl Fn(&mut Troika)) {
         let mut hash_length = hash.len();
         let mut hash_idx = 0;
 
-        while hash_length >= TROIKA_RATE {
-            self.permutation();
-            // Extract rate output
-            for trit_idx in 0..TROIKA_RATE {
-                hash[hash_idx + trit_idx] = self.state[trit_idx];
+        while hash_length > 0 {
+            self.permute_squeezed(&permutation);
+            let space = (TROIKA_RATE - self.idx).min(hash_length);
+            hash[hash_idx..hash_idx + space]
+                .copy_from_slice(&self.state[self.idx..self.idx + space]);
+            self.idx += space;
+            hash_idx += space;
+            hash_length -= space;
+        }
+    }
+
+    fn absorb_sequence_with(&mut self, trits: &[Trit], permutation: impl Fn(&mut Troika)) {
+        let mut trits = trits;
+
+        while !trits.is_empty() {
+            // Start a new block with an empty rate
+            if self.idx == 0 {
+                self.state[..TROIKA_RATE].copy_from_slice(&[0; TROIKA_RATE]);
             }
-            hash_idx += TROIKA_RATE;
-            hash_length -= TROIKA_RATE;
+            let space = (TROIKA_RATE - self.idx).min(trits.len());
+            self.state[self.idx..self.idx + space].copy_from_slice(&trits[..space]);
+            self.idx += space;
+            trits = &trits[space..];
+
+            if self.idx == TROIKA_RATE {
+                permutation(self);
+                self.idx = 0;
+            }
+        }
+    }
+
+    /// Squeezing stops at the end of the rate and only permutes once
+    /// the sponge is used again, so a one-shot hash does not pay for a
+    /// block nobody reads.
+    fn permute_squeezed(&mut self, permutation: &impl Fn(&mut Troika)) {
+        if self.idx == TROIKA_RATE {
+            permutation(self);
+            self.idx = 0;
+        }
+    }
+
+    fn absorb_padding_with(&mut self, permutation: impl Fn(&mut Troika)) {
+        self.permute_squeezed(&permutation);
+        if self.idx == 0 {
+            self.state[..TROIKA_RATE].copy_from_slice(&[0; TROIKA_RATE]);
         }
+        self.state[self.idx] = PADDING;
+        permutation(self);
+        self.idx = 0;
+    }
+}
+
+impl From<Ftroika> for Troika {
+    /// Move the whole sponge, including its position in the rate,
+    /// to the flat layout.
+    fn from(ftroika: Ftroika) -> Troika {
+        let mut troika = Troika::default();
+        troika.restore(&ftroika.midstate());
+        troika
+    }
+}
+
+impl Permutation for Troika {
+    fn permute_unchecked(state: &mut [Trit; STATE_SIZE], num_rounds: usize) {
+        let mut troika = Troika {
+            num_rounds,
+            state: *state,
+            ..Troika::default()
+        };
+        troika.permutation();
+        *state = troika.state;
+    }
+
+    fn inverse_unchecked(state: &mut [Trit; STATE_SIZE], num_rounds: usize) {
+        let mut troika = Troika {
+            num_rounds,
+            state: *state,
+            ..Troika::default()
+        };
+        troika.inverse_rounds();
+        *state = troika.state;
+    }
+}
 
-        // Check if there is a last incomplete block
-        if hash_length % TROIKA_RATE != 0 {
-            self.permutation();
-            for trit_idx in 0..hash_length {
-                hash[trit_idx] = self.state[trit_idx];
+#[cfg(feature = "research")]
+impl crate::research::RoundSteps for Troika {
+    fn from_state(state: &[Trit; STATE_SIZE]) -> Troika {
+        Troika {
+            state: *state,
+            ..Troika::default()
+        }
+    }
+
+    fn to_state(&self) -> [Trit; STATE_SIZE] {
+        self.state
+    }
+
+    fn sub_trytes(&mut self) {
+        Troika::sub_trytes(self);
+    }
+
+    fn shift_rows(&mut self) {
+        let mut new_state = [0u8; STATE_SIZE];
+        for (old, new) in self
+            .state
+            .chunks(SLICESIZE)
+            .zip(new_state.chunks_mut(SLICESIZE))
+        {
+            for (&trit, &rowcol) in old.iter().zip(SHIFT_ROWS.iter()) {
+                new[rowcol as usize] = trit;
             }
         }
+        self.state = new_state;
+    }
+
+    fn shift_lanes(&mut self) {
+        let mut new_state = [0u8; STATE_SIZE];
+        for (idx, &trit) in self.state.iter().enumerate() {
+            let (slice, rowcol) = (idx / SLICESIZE, idx % SLICESIZE);
+            let slice = (slice + SHIFT_LANES[rowcol] as usize) % SLICES;
+            new_state[SLICESIZE * slice + rowcol] = trit;
+        }
+        self.state = new_state;
     }
 
-    fn reset(&mut self) {
-        self.state = [0; STATE_SIZE];
+    fn shift_rows_lanes(&mut self) {
+        Troika::shift_rows_lanes(self);
+    }
+
+    fn add_column_parity(&mut self) {
+        Troika::add_column_parity(self);
+    }
+
+    fn add_round_constant(&mut self, round: usize) {
+        Troika::add_round_constant(self, round);
+    }
+
+    fn inverse_sub_trytes(&mut self) {
+        Troika::inverse_sub_trytes(self);
+    }
+
+    fn inverse_shift_rows_lanes(&mut self) {
+        Troika::inverse_shift_rows_lanes(self);
+    }
+
+    fn inverse_add_column_parity(&mut self) {
+        Troika::inverse_add_column_parity(self);
+    }
+
+    fn inverse_add_round_constant(&mut self, round: usize) {
+        Troika::inverse_add_round_constant(self, round);
     }
 }
 
 impl Troika {
+    /// Create a sponge with a reduced number of rounds.
+    /// Fails unless `num_rounds` is in `1..=NUM_ROUNDS`.
     pub fn new(num_rounds: usize) -> Result<Troika> {
-        let mut troika = Troika::default();
-        troika.num_rounds = num_rounds;
-        Ok(troika)
+        check_rounds(num_rounds)?;
+        Ok(Troika {
+            num_rounds,
+            ..Troika::default()
+        })
+    }
+
+    /// Switch to the round count and tables of a `TroikaParams`.
+    pub(crate) fn set_params(&mut self, num_rounds: usize, tables: &'static Tables) {
+        self.num_rounds = num_rounds;
+        self.tables = tables;
     }
 
     pub fn state(&self) -> &[Trit] {
         &self.state
     }
 
+    /// Take a snapshot of the sponge.
+    pub fn midstate(&self) -> Midstate {
+        Midstate::new(self.num_rounds, self.idx, self.state)
+    }
+
+    /// Continue from a snapshot taken on any backend.
+    pub fn restore(&mut self, midstate: &Midstate) {
+        self.num_rounds = midstate.num_rounds();
+        self.idx = midstate.idx();
+        self.state = *midstate.state();
+    }
+
     pub fn permutation(&mut self) {
         assert!(self.num_rounds <= NUM_ROUNDS);
 
-        for round in 0..self.num_rounds {
-            self.sub_trytes();
-            self.shift_rows_lanes();
-            self.add_column_parity();
-            self.add_round_constant(round);
+        let tables = self.tables;
+        let mut scratch = [0; STATE_SIZE];
+        for constants in tables.round_constants[..self.num_rounds].iter() {
+            self.round(&mut scratch, &tables.sbox_trits, constants);
         }
     }
 
-    fn sub_trytes(&mut self) {
+    /// `ROUNDS` rounds of standard Troika, unrolled.
+    fn fixed_permutation<const ROUNDS: usize>(&mut self) {
+        let sbox_trits = &self.tables.sbox_trits;
+        let mut scratch = [0; STATE_SIZE];
+        unroll_rounds!(ROUNDS, |round| {
+            self.round(&mut scratch, sbox_trits, &ROUND_CONSTANTS[round]);
+        });
+    }
+
+    /// One round with its steps fused into three passes: the S-box
+    /// outputs are scattered to their shifted positions in `scratch`,
+    /// the column parities are summed, and the mixed trits are written
+    /// back to the state together with the round constants. `scratch`
+    /// is only reused between rounds, its contents do not matter.
+    #[inline(always)]
+    fn round(
+        &mut self,
+        scratch: &mut [Trit; STATE_SIZE],
+        sbox_trits: &[[Trit; 3]; 27],
+        constants: &[Trit; COLUMNS * SLICES],
+    ) {
+        for (tryte, dest) in self
+            .state
+            .chunks_exact(3)
+            .zip(SHIFT_ROWS_LANES_DEST.chunks_exact(3))
+        {
+            let input = 9 * tryte[0] + 3 * tryte[1] + tryte[2];
+            let output = &sbox_trits[usize::from(input)];
+            scratch[usize::from(dest[0])] = output[0];
+            scratch[usize::from(dest[1])] = output[1];
+            scratch[usize::from(dest[2])] = output[2];
+        }
+
+        // Per column of every slice, the sum of its two neighbouring parities
+        let mut parity = [0; COLUMNS * SLICES];
+        for (slice, parity) in scratch
+            .chunks_exact(SLICESIZE)
+            .zip(parity.chunks_exact_mut(COLUMNS))
+        {
+            let (row0, rows12) = slice.split_at(COLUMNS);
+            let (row1, row2) = rows12.split_at(COLUMNS);
+            for (((col_sum, &a), &b), &c) in parity.iter_mut().zip(row0).zip(row1).zip(row2) {
+                *col_sum = (a + b + c) % 3;
+            }
+        }
+        let mut sums = [0; COLUMNS * SLICES];
+        for (sum, &[left, right]) in sums.iter_mut().zip(PARITY_NEIGHBOURS.iter()) {
+            *sum = parity[usize::from(left)] + parity[usize::from(right)];
+        }
+
+        for (((state, shifted), sums), constants) in self
+            .state
+            .chunks_exact_mut(SLICESIZE)
+            .zip(scratch.chunks_exact(SLICESIZE))
+            .zip(sums.chunks_exact(COLUMNS))
+            .zip(constants.chunks_exact(COLUMNS))
+        {
+            for (row, (state, shifted)) in state
+                .chunks_exact_mut(COLUMNS)
+                .zip(shifted.chunks_exact(COLUMNS))
+                .enumerate()
+            {
+                for (((trit, &shifted), &sum), &constant) in
+                    state.iter_mut().zip(shifted).zip(sums).zip(constants)
+                {
+                    let constant = if row == 0 { constant } else { 0 };
+                    *trit = (shifted + sum + constant) % 3;
+                }
+            }
+        }
+    }
+
+    /// Undo the first `num_rounds` rounds, last round first.
+    fn inverse_rounds(&mut self) {
+        assert!(self.num_rounds <= NUM_ROUNDS);
+
+        for round in (0..self.num_rounds).rev() {
+            self.inverse_add_round_constant(round);
+            self.inverse_add_column_parity();
+            self.inverse_shift_rows_lanes();
+            self.inverse_sub_trytes();
+        }
+    }
+
+    // The separate steps, which the permutation fuses into one round
+    #[cfg(any(test, feature = "research"))]
+    pub(crate) fn sub_trytes(&mut self) {
+        self.substitute(&self.tables.sbox);
+    }
+
+    pub(crate) fn inverse_sub_trytes(&mut self) {
+        self.substitute(&self.tables.inv_sbox);
+    }
+
+    fn substitute(&mut self, sbox: &[Trit; 27]) {
         for sbox_idx in 0..NUM_SBOXES {
             let sbox_input = 9 * self.state[3 * sbox_idx]
                 + 3 * self.state[3 * sbox_idx + 1]
                 + self.state[3 * sbox_idx + 2];
-            let mut sbox_output = SBOX_LOOKUP[sbox_input as usize];
+            let mut sbox_output = sbox[sbox_input as usize];
             self.state[3 * sbox_idx + 2] = sbox_output % 3;
             sbox_output /= 3;
             self.state[3 * sbox_idx + 1] = sbox_output % 3;
@@ -132,7 +414,8 @@ impl Troika {
         }
     }
 
-    fn shift_rows_lanes(&mut self) {
+    #[cfg(any(test, feature = "research"))]
+    pub(crate) fn shift_rows_lanes(&mut self) {
         let mut new_state = [0u8; STATE_SIZE];
         for i in 0..STATE_SIZE {
             new_state[i] = self.state[SHIFT_ROWS_LANES[i]];
@@ -141,7 +424,28 @@ impl Troika {
         self.state = new_state;
     }
 
-    fn add_column_parity(&mut self) {
+    pub(crate) fn inverse_shift_rows_lanes(&mut self) {
+        let mut new_state = [0u8; STATE_SIZE];
+        for (&trit, &idx) in self.state.iter().zip(SHIFT_ROWS_LANES.iter()) {
+            new_state[idx] = trit;
+        }
+        self.state = new_state;
+    }
+
+    #[cfg(any(test, feature = "research"))]
+    pub(crate) fn add_column_parity(&mut self) {
+        self.mix_column_parity(1);
+    }
+
+    pub(crate) fn inverse_add_column_parity(&mut self) {
+        // Every trit of a column gets the same sum added, which leaves
+        // the column parities unchanged. Subtracting the sums computed
+        // from the mixed state therefore undoes the mixing.
+        self.mix_column_parity(2);
+    }
+
+    /// Add `factor` times the parities of the neighbouring columns.
+    fn mix_column_parity(&mut self, factor: Trit) {
         let mut parity = [0u8; SLICES * COLUMNS];
 
         // First compute parity for each column
@@ -162,26 +466,94 @@ impl Troika {
                     let idx = SLICESIZE * slice + COLUMNS * row + col;
                     let sum_to_add = parity[(col + 8) % 9 + COLUMNS * slice]
                         + parity[(col + 1) % 9 + COLUMNS * ((slice + 1) % SLICES)];
-                    self.state[idx] = (self.state[idx] + sum_to_add) % 3;
+                    self.state[idx] = (self.state[idx] + factor * sum_to_add) % 3;
                 }
             }
         }
     }
 
-    fn add_round_constant(&mut self, round: usize) {
+    #[cfg(any(test, feature = "research"))]
+    pub(crate) fn add_round_constant(&mut self, round: usize) {
+        self.mix_round_constant(&self.tables.round_constants[round], 1);
+    }
+
+    pub(crate) fn inverse_add_round_constant(&mut self, round: usize) {
+        self.mix_round_constant(&self.tables.round_constants[round], 2);
+    }
+
+    /// Add `factor` times the constants of a round.
+    fn mix_round_constant(&mut self, constants: &[Trit; COLUMNS * SLICES], factor: Trit) {
         for slice in 0..SLICES {
             for col in 0..COLUMNS {
                 let idx = SLICESIZE * slice + col;
-                self.state[idx] =
-                    (self.state[idx] + ROUND_CONSTANTS[round][slice * COLUMNS + col]) % 3;
+                self.state[idx] = (self.state[idx] + factor * constants[slice * COLUMNS + col]) % 3;
             }
         }
     }
 }
 
+/// The FixedTroika struct is a Troika sponge whose round count is part
+/// of its type, so an invalid count fails to compile. Its rounds are
+/// unrolled and read the round constants directly.
+#[derive(Clone, Copy, Debug)]
+pub struct FixedTroika<const ROUNDS: usize> {
+    troika: Troika,
+}
+
+/// Standard Troika with a compile-time round count.
+pub type Troika24 = FixedTroika<NUM_ROUNDS>;
+
+impl<const ROUNDS: usize> Default for FixedTroika<ROUNDS> {
+    fn default() -> FixedTroika<ROUNDS> {
+        let mut troika = Troika::default();
+        troika.set_params(ROUNDS, Tables::of::<Rounds<ROUNDS>>());
+        FixedTroika { troika }
+    }
+}
+
+impl<const ROUNDS: usize> FixedTroika<ROUNDS> {
+    pub fn state(&self) -> &[Trit] {
+        self.troika.state()
+    }
+
+    /// Take a snapshot of the sponge.
+    pub fn midstate(&self) -> Midstate {
+        self.troika.midstate()
+    }
+}
+
+impl<const ROUNDS: usize> Sponge for FixedTroika<ROUNDS> {
+    fn absorb(&mut self, message: &[Trit]) {
+        self.troika
+            .absorb_with(message, Troika::fixed_permutation::<ROUNDS>);
+    }
+
+    fn squeeze(&mut self, hash: &mut [Trit]) {
+        self.troika
+            .squeeze_with(hash, Troika::fixed_permutation::<ROUNDS>);
+    }
+
+    fn reset(&mut self) {
+        self.troika.reset();
+    }
+}
+
+impl<const ROUNDS: usize> StreamingSponge for FixedTroika<ROUNDS> {
+    fn absorb_sequence(&mut self, trits: &[Trit]) {
+        self.troika
+            .absorb_sequence_with(trits, Troika::fixed_permutation::<ROUNDS>);
+    }
+
+    fn absorb_padding(&mut self) {
+        self.troika
+            .absorb_padding_with(Troika::fixed_permutation::<ROUNDS>);
+    }
+}
+
 #[cfg(test)]
 mod test_troika {
     use super::*;
+    use crate::Error;
 
     const HASH: [u8; 243] = [
         0, 2, 2, 1, 2, 1, 0, 1, 2, 1, 1, 1, 1, 2, 2, 1, 1, 1, 0, 1, 2, 1, 2, 1, 2, 1, 2, 1, 2, 2,
@@ -208,4 +580,84 @@ mod test_troika {
             "Arrays are not equal"
         );
     }
+
+    #[test]
+    fn test_lazy_permutation() {
+        let count = core::cell::Cell::new(0);
+        let permutation = |troika: &mut Troika| {
+            count.set(count.get() + 1);
+            troika.permutation();
+        };
+        let mut troika = Troika::default();
+        let mut output = [0u8; 243];
+        troika.absorb_with(&[0; 243], permutation);
+        troika.squeeze_with(&mut output, permutation);
+        assert_eq!(output[..], HASH[..]);
+        assert_eq!(count.get(), 2);
+        troika.squeeze_with(&mut output[..1], permutation);
+        assert_eq!(count.get(), 3);
+    }
+
+    #[test]
+    fn test_new() {
+        assert!(Troika::new(1).is_ok());
+        assert!(Troika::new(NUM_ROUNDS).is_ok());
+        assert_eq!(
+            Troika::new(0).unwrap_err(),
+            Error::InvalidRounds {
+                rounds: 0,
+                max: NUM_ROUNDS
+            }
+        );
+        assert_eq!(
+            Troika::new(NUM_ROUNDS + 1).unwrap_err(),
+            Error::InvalidRounds {
+                rounds: NUM_ROUNDS + 1,
+                max: NUM_ROUNDS
+            }
+        );
+    }
+
+    #[test]
+    fn test_fixed_rounds() {
+        let mut input = [0u8; 600];
+        for (i, trit) in input.iter_mut().enumerate() {
+            *trit = ((i * 5 + i / 9) % 3) as Trit;
+        }
+        fn check<S: Sponge>(mut sponge: S, mut troika: Troika, input: &[Trit]) {
+            let mut output = [0u8; 500];
+            let mut expected = [0u8; 500];
+            sponge.absorb(input);
+            sponge.squeeze(&mut output);
+            troika.absorb(input);
+            troika.squeeze(&mut expected);
+            assert_eq!(output[..], expected[..]);
+        }
+        check(Troika24::default(), Troika::default(), &input);
+        check(FixedTroika::<1>::default(), Troika::new(1).unwrap(), &input);
+        check(FixedTroika::<9>::default(), Troika::new(9).unwrap(), &input);
+        assert_eq!(FixedTroika::<9>::default().midstate().num_rounds(), 9);
+    }
+
+    #[test]
+    fn test_round_matches_steps() {
+        let mut state = [0; STATE_SIZE];
+        for (i, trit) in state.iter_mut().enumerate() {
+            *trit = ((i * i + i / 3) % 3) as Trit;
+        }
+        let mut fused = Troika::default();
+        let mut steps = Troika::default();
+        fused.state = state;
+        steps.state = state;
+
+        let mut scratch = [0; STATE_SIZE];
+        for (round, constants) in ROUND_CONSTANTS.iter().enumerate() {
+            fused.round(&mut scratch, &fused.tables.sbox_trits, constants);
+            steps.sub_trytes();
+            steps.shift_rows_lanes();
+            steps.add_column_parity();
+            steps.add_round_constant(round);
+            assert_eq!(fused.state[..], steps.state[..], "round {}", round);
+        }
+    }
 }
@@ -1,7 +1,7 @@
+use rand::{thread_rng, Rng};
 use troika::ftroika::*;
 use troika::troika::*;
 use troika::Sponge;
-use rand::{thread_rng, Rng};
 
 #[test]
 fn random_hash() {
@@ -31,3 +31,33 @@ fn random_hash() {
         );
     }
 }
+
+#[test]
+fn all_message_lengths() {
+    let mut ftroika = Ftroika::default();
+    let mut troika = Troika::default();
+    let mut foutput = [0u8; 243];
+    let mut output = [0u8; 243];
+    let mut input = vec![0u8; 4000];
+    let mut rng = thread_rng();
+
+    for trit in input.iter_mut() {
+        *trit = rng.gen_range(0, 3);
+    }
+
+    for length in 0..input.len() {
+        ftroika.absorb(&input[..length]);
+        ftroika.squeeze(&mut foutput);
+        ftroika.reset();
+
+        troika.absorb(&input[..length]);
+        troika.squeeze(&mut output);
+        troika.reset();
+
+        assert!(
+            foutput.iter().zip(output.iter()).all(|(a, b)| a == b),
+            "Arrays are not equal for message length {}",
+            length
+        );
+    }
+}